@@ -0,0 +1,9 @@
+use libewmh::WindowManager;
+
+// Print the window manager events as they happen
+fn main() {
+    let wm = WindowManager::connect().unwrap();
+    for event in wm.events().unwrap() {
+        println!("{:?}", event.unwrap());
+    }
+}
@@ -9,7 +9,7 @@ fn main() {
     println!("Window Manager:    {}", wm_name);
     println!("Composite Manager: {}", wm.composite_manager().unwrap());
     println!("Root Window:       {}", wm.root());
-    println!("Work area:         {}x{}", wm.work_width(), wm.work_height());
+    println!("Work area:         {}", wm.work_area());
     println!("Screen Size:       {}x{}", wm.width(), wm.height());
     println!("Desktops:          {}", wm.desktops().unwrap());
     println!();
@@ -17,8 +17,8 @@ fn main() {
     println!("{:-<120}", "");
 
     println!(
-        "{:<8} {:<3} {:<6} {:<5} {:<5} {:<4} {:<4} {:<8} {:<7} {:<18} {:<18} {}",
-        "ID", "DSK", "PID", "X", "Y", "W", "H", "BORDERS", "TYPE", "STATE", "CLASS", "NAME"
+        "{:<8} {:<3} {:<6} {:<5} {:<5} {:<4} {:<4} {:<8} {:<7} {:<18} {:<18} NAME",
+        "ID", "DSK", "PID", "X", "Y", "W", "H", "BORDERS", "TYPE", "STATE", "CLASS"
     );
 
     let pid = wm.win_pid(win).unwrap_or(-1);
@@ -87,5 +87,8 @@ atom_manager! {
       _NET_WM_WINDOW_TYPE_UTILITY,
       _NET_WORKAREA,
       UTF8_STRING,
+      WM_DELETE_WINDOW,
+      WM_PROTOCOLS,
+      WM_WINDOW_ROLE,
   }
 }
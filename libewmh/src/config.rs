@@ -0,0 +1,198 @@
+//! `Config` provides user defined shapes, positions, command aliases and window rules loaded from
+//! `~/.config/wmcli/config.toml`. Once installed with `config::init` the custom shape and position
+//! names are resolved by `WinShape::try_from` and `WinPosition::try_from` alongside the built-ins.
+//!
+//! ```toml
+//! # Dimensions up to 1.0 are fractions of the work area while larger values are pixels. When an
+//! # aspect ratio is given any missing dimension is derived from it else the shape is fit within
+//! # the given dimensions.
+//! [shapes]
+//! third = { width = 0.333, height = 1.0 }
+//! video = { width = 1280, aspect = 1.7778 }
+//!
+//! # Positions align the window like the built-in positions after insetting the work area by
+//! # the given margin in pixels.
+//! [positions]
+//! corner = { align = "bottom-right", margin = 20 }
+//!
+//! # Aliases expand to the given command line arguments
+//! [aliases]
+//! left-third = "window place third left"
+//!
+//! # Rules are applied by `wmcli daemon` to new windows, see `Rule` for the details
+//! [[rules]]
+//! class = "firefox"
+//! shape = "halfw"
+//! position = "right"
+//! ```
+use std::{collections::HashMap, sync::OnceLock};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Rule, Size, WinPosition};
+
+#[cfg(feature = "config")]
+use crate::{WindowManagerError, WindowManagerResult};
+
+// Configuration used to resolve custom shapes and positions
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Config provides the user's custom shapes, positions, command aliases and window rules
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct Config {
+    pub shapes: HashMap<String, CustomShape>,
+    pub positions: HashMap<String, CustomPosition>,
+    pub aliases: HashMap<String, String>,
+    pub rules: Vec<Rule>,
+}
+
+/// CustomShape provides a user defined shape as fractions of the work area or as pixels
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct CustomShape {
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    pub aspect: Option<f32>,
+}
+
+impl CustomShape {
+    /// Calculate the client size of the shape. Returns None when the shape defines nothing.
+    ///
+    /// ### Arguments
+    /// * `full` - client size that would fill the work area
+    /// * `current` - current client size of the window used for an unspecified dimension
+    pub fn size(&self, full: Size, current: Size) -> Option<Size> {
+        let dim = |val: f32, full: i32| if val <= 1.0 { (full as f32 * val) as i32 } else { val as i32 };
+        let (w, h) = match (self.width, self.height, self.aspect.filter(|x| *x > 0.0)) {
+            (None, None, None) => return None,
+            (Some(w), None, None) => (dim(w, full.w), current.h),
+            (None, Some(h), None) => (current.w, dim(h, full.h)),
+            (Some(w), Some(h), None) => (dim(w, full.w), dim(h, full.h)),
+            (Some(w), None, Some(a)) => (dim(w, full.w), (dim(w, full.w) as f32 / a) as i32),
+            (None, Some(h), Some(a)) => ((dim(h, full.h) as f32 * a) as i32, dim(h, full.h)),
+            (w, h, Some(a)) => {
+                // Fit the aspect ratio within the given dimensions defaulting to the work area
+                let (w, h) = (dim(w.unwrap_or(1.0), full.w), dim(h.unwrap_or(1.0), full.h));
+                if w as f32 / a <= h as f32 {
+                    (w, (w as f32 / a) as i32)
+                } else {
+                    ((h as f32 * a) as i32, h)
+                }
+            },
+        };
+        Some(Size::new(w.max(1), h.max(1)))
+    }
+}
+
+/// CustomPosition provides a user defined position aligned like a built-in position within the
+/// work area inset by a margin.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct CustomPosition {
+    pub align: WinPosition,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub margin: i32,
+}
+
+#[cfg(feature = "config")]
+impl Config {
+    /// Get the path of the user's config file i.e. `$XDG_CONFIG_HOME/wmcli/config.toml` falling
+    /// back to `~/.config/wmcli/config.toml`
+    pub fn path() -> Option<std::path::PathBuf> {
+        let dir = match std::env::var_os("XDG_CONFIG_HOME").filter(|x| !x.is_empty()) {
+            Some(dir) => std::path::PathBuf::from(dir),
+            None => std::path::PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+        Some(dir.join("wmcli").join("config.toml"))
+    }
+
+    /// Load the user's config file returning an empty config if it doesn't exist
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// libewmh::config::init(Config::load().unwrap());
+    /// ```
+    pub fn load() -> WindowManagerResult<Config> {
+        let path = match Config::path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Config::default()),
+        };
+        let err = |err: &dyn std::fmt::Display| format!("{}: {}", path.display(), err);
+        let data = std::fs::read_to_string(&path).map_err(|x| WindowManagerError::InvalidConfig(err(&x)))?;
+        Config::try_from(data.as_str()).map_err(|x| match x {
+            WindowManagerError::InvalidConfig(x) => WindowManagerError::InvalidConfig(err(&x)).into(),
+            x => x.into(),
+        })
+    }
+}
+
+// Convert from the toml &str to a Config
+#[cfg(feature = "config")]
+impl std::convert::TryFrom<&str> for Config {
+    type Error = WindowManagerError;
+
+    fn try_from(val: &str) -> Result<Self, Self::Error> {
+        toml::from_str(val).map_err(|x| WindowManagerError::InvalidConfig(x.message().to_string()))
+    }
+}
+
+/// Install the config used to resolve custom shapes and positions. Only the first config installed
+/// is used and false is returned if one was already installed.
+///
+/// ### Arguments
+/// * `config` - config to install
+pub fn init(config: Config) -> bool {
+    CONFIG.set(config).is_ok()
+}
+
+/// Get the installed config or an empty config if none was installed
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+#[cfg(all(test, feature = "config"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config() {
+        let config = Config::try_from(
+            r#"
+            [shapes]
+            third = { width = 0.5, height = 1.0 }
+            video = { width = 1280, aspect = 1.6 }
+            fit = { aspect = 1.0 }
+
+            [positions]
+            corner = { align = "bottom-right", margin = 20 }
+
+            [aliases]
+            left-third = "window place third left"
+
+            [[rules]]
+            class = "firefox"
+            type = "normal"
+            states = ["above", "sticky"]
+            "#,
+        )
+        .unwrap();
+        let (full, current) = (Size::new(1920, 1000), Size::new(500, 500));
+        assert_eq!(config.shapes["third"].size(full, current), Some(Size::new(960, 1000)));
+        assert_eq!(config.shapes["video"].size(full, current), Some(Size::new(1280, 800)));
+        assert_eq!(config.shapes["fit"].size(full, current), Some(Size::new(1000, 1000)));
+        assert_eq!(config.positions["corner"], CustomPosition { align: WinPosition::BottomRight, margin: 20 });
+        assert_eq!(config.aliases["left-third"], "window place third left");
+        assert_eq!(config.rules[0].selector().unwrap().to_string(), "class:firefox,type:normal");
+        assert!(config.rules[0].check().is_ok());
+        assert!(Rule { shape: Some("huge".to_string()), ..config.rules[0].clone() }.check().is_err());
+        assert!(Rule::default().check().is_err());
+
+        assert!(Config::try_from("[shapes]\nthird = { wide = 0.3 }").is_err());
+    }
+}
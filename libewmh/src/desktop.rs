@@ -0,0 +1,147 @@
+use tracing::debug;
+
+use crate::{
+    geometry, window, window::WinOpt, DesktopInfo, Layout, Rect, WinInfo, WinSelector, WinState, WinType,
+    WindowManager, WindowManagerError, WindowManagerResult,
+};
+
+/// Get the desktops the window manager is managing marking the current desktop and giving a
+/// count of the windows on each desktop.
+///
+/// ### Examples
+/// ```ignore
+/// use libewmh::prelude::*;
+/// let desktops = libewmh::desktop::list().unwrap();
+/// ```
+pub fn list() -> WindowManagerResult<Vec<DesktopInfo>> {
+    let wmcli = WindowManager::connect()?;
+    let current = wmcli.current_desktop()?;
+    let names = wmcli.desktop_names().unwrap_or_default();
+
+    // Count the windows on each desktop skipping those shown on all desktops
+    let desktops = wmcli.desktops()?;
+    let mut counts = vec![0; desktops as usize];
+    for win in wmcli.get_windows(false)? {
+        if let Ok(desktop) = wmcli.win_desktop(win.id) {
+            if desktop >= 0 && (desktop as u32) < desktops {
+                counts[desktop as usize] += 1;
+            }
+        }
+    }
+
+    Ok(counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, windows)| DesktopInfo {
+            index: i as u32,
+            name: names.get(i).cloned().unwrap_or_default(),
+            current: i as u32 == current,
+            windows,
+        })
+        .collect())
+}
+
+/// Switch to the given desktop
+///
+/// ### Arguments
+/// * `desktop` - index or name of the desktop to switch to
+///
+/// ### Examples
+/// ```ignore
+/// use libewmh::prelude::*;
+/// libewmh::desktop::switch("1").unwrap();
+/// ```
+pub fn switch(desktop: &str) -> WindowManagerResult<()> {
+    let wmcli = WindowManager::connect()?;
+    let desktop = resolve(&wmcli, desktop)?;
+    wmcli.set_current_desktop(desktop)
+}
+
+/// Close the last desktop. EWMH only allows for changing the number of desktops so any windows
+/// on the last desktop will be moved by the window manager to the new last desktop.
+///
+/// ### Examples
+/// ```ignore
+/// use libewmh::prelude::*;
+/// libewmh::desktop::close().unwrap();
+/// ```
+pub fn close() -> WindowManagerResult<()> {
+    let wmcli = WindowManager::connect()?;
+    let desktops = wmcli.desktops()?;
+    if desktops <= 1 {
+        return Err(WindowManagerError::InvalidDesktop("can't close the only desktop".to_owned()).into());
+    }
+    wmcli.set_desktops(desktops - 1)
+}
+
+/// Tile the normal windows of the desktop in the given layout separately on each monitor. Windows
+/// that are minimized, fullscreen or kept above the others are left floating.
+///
+/// ### Arguments
+/// * `desktop` - index or name of the desktop to tile else the current desktop
+/// * `selector` - only tile the windows matching the given selector
+/// * `layout` - layout to arrange the windows in
+/// * `ratio` - fraction of the work area's width given to the master window
+/// * `gap` - pixels to leave between the windows and around the edges of the work area
+///
+/// ### Examples
+/// ```ignore
+/// use libewmh::prelude::*;
+/// libewmh::desktop::tile(None, None, Layout::MasterStack, 0.55, 8).unwrap();
+/// ```
+pub fn tile(
+    desktop: Option<&str>, selector: Option<&WinSelector>, layout: Layout, ratio: f32, gap: u32,
+) -> WindowManagerResult<()> {
+    let wmcli = WindowManager::connect()?;
+    let desktop = match desktop {
+        Some(desktop) => resolve(&wmcli, desktop)?,
+        None => wmcli.current_desktop()?,
+    } as i32;
+
+    // Group the windows by the work area of the monitor containing their center
+    let mut groups: Vec<(Rect, Vec<u32>)> = vec![];
+    for win in wmcli.get_windows(false)? {
+        let info = match wmcli.win_info(win.id) {
+            Ok(info) if info.desktop == desktop && tileable(&info) => info,
+            _ => continue,
+        };
+        if !selector.map(|x| x.is_match(&wmcli, info.id)).unwrap_or(true) {
+            continue;
+        }
+        let area = window::work_area(&wmcli, None, &window::outer_rect(&info))?;
+        match groups.iter_mut().find(|(x, _)| *x == area) {
+            Some((_, wins)) => wins.push(info.id),
+            None => groups.push((area, vec![info.id])),
+        }
+    }
+
+    for (area, wins) in groups {
+        for (win, rect) in wins.iter().zip(geometry::tile(&area, wins.len(), layout, ratio, gap as i32)) {
+            // A window closing mid retile shouldn't leave the rest untiled
+            if let Err(err) = WinOpt::new(Some(*win)).rect(rect).place() {
+                debug!("tile: skipping id: {}, {}", win, err);
+            }
+        }
+    }
+    Ok(())
+}
+
+// Check if the window should be tiled i.e. a normal window that isn't minimized, fullscreen or
+// kept above the others
+fn tileable(info: &WinInfo) -> bool {
+    matches!(info.typ, None | Some(WinType::Normal))
+        && !info.state.iter().any(|x| matches!(x, WinState::Above | WinState::Fullscreen | WinState::Hidden))
+}
+
+// Resolve the given desktop index or name into a desktop index
+pub(crate) fn resolve(wmcli: &WindowManager, desktop: &str) -> WindowManagerResult<u32> {
+    if let Ok(index) = desktop.parse::<u32>() {
+        return Ok(index);
+    }
+    wmcli
+        .desktop_names()?
+        .iter()
+        .position(|x| x == desktop)
+        .map(|x| x as u32)
+        .ok_or_else(|| WindowManagerError::InvalidDesktop(desktop.to_owned()).into())
+}
@@ -9,16 +9,30 @@ pub type WindowManagerResult<T> = std::result::Result<T, ErrorWrapper>;
 pub enum WindowManagerError {
     DesktopWinNotFound,
     InvalidAtom(String),
+    InvalidConfig(String),
+    InvalidDesktop(String),
+    InvalidDirection(String),
+    InvalidGeometry(String),
+    InvalidGrid(String),
+    InvalidLayout(String),
+    InvalidMonitor(String),
+    InvalidSnapshot(String),
     InvalidWinGravity(u32),
     InvalidWinPosition(String),
     InvalidWinShape(String),
     InvalidWinClass(u32),
     InvalidWinMap(u32),
     InvalidWinState(u32),
+    InvalidWinStateAction(String),
+    InvalidWinStateName(String),
     InvalidWinType(u32),
+    InvalidWinSelector(String),
     PropertyNotFound,
     TaskbarNotFound,
     TaskbarReservationNotFound,
+    WinAmbiguous(String, usize),
+    WinDeleteNotSupported(u32),
+    WinNotFound(String),
 }
 impl std::error::Error for WindowManagerError {}
 impl fmt::Display for WindowManagerError {
@@ -26,16 +40,40 @@ impl fmt::Display for WindowManagerError {
         match *self {
             WindowManagerError::DesktopWinNotFound => write!(f, "desktop window was not found"),
             WindowManagerError::InvalidAtom(ref err) => write!(f, "invalid atom was given: {}", err),
+            WindowManagerError::InvalidConfig(ref err) => write!(f, "invalid config was given: {}", err),
+            WindowManagerError::InvalidDesktop(ref err) => write!(f, "invalid desktop was given: {}", err),
+            WindowManagerError::InvalidDirection(ref err) => write!(f, "invalid direction was given: {}", err),
+            WindowManagerError::InvalidGeometry(ref err) => write!(f, "invalid geometry was given: {}", err),
+            WindowManagerError::InvalidGrid(ref err) => write!(f, "invalid grid was given: {}", err),
+            WindowManagerError::InvalidLayout(ref err) => write!(f, "invalid layout was given: {}", err),
+            WindowManagerError::InvalidMonitor(ref err) => write!(f, "invalid monitor was given: {}", err),
+            WindowManagerError::InvalidSnapshot(ref err) => {
+                write!(f, "invalid layout snapshot was given: {}", err)
+            },
             WindowManagerError::InvalidWinGravity(ref err) => write!(f, "invalid gravity was given: {}", err),
             WindowManagerError::InvalidWinPosition(ref err) => write!(f, "invalid position was given: {}", err),
             WindowManagerError::InvalidWinShape(ref err) => write!(f, "invalid shape was given: {}", err),
             WindowManagerError::InvalidWinClass(ref err) => write!(f, "invalid class was given: {}", err),
             WindowManagerError::InvalidWinMap(ref err) => write!(f, "invalid map was given: {}", err),
             WindowManagerError::InvalidWinState(ref err) => write!(f, "invalid state was given: {}", err),
+            WindowManagerError::InvalidWinStateAction(ref err) => {
+                write!(f, "invalid state action was given: {}", err)
+            },
+            WindowManagerError::InvalidWinStateName(ref err) => write!(f, "invalid state was given: {}", err),
             WindowManagerError::InvalidWinType(ref err) => write!(f, "invalid type was given: {}", err),
+            WindowManagerError::InvalidWinSelector(ref err) => {
+                write!(f, "invalid window selector was given: {}", err)
+            },
             WindowManagerError::PropertyNotFound => write!(f, "an x11 property was not found"),
             WindowManagerError::TaskbarNotFound => write!(f, "taskbar not found"),
             WindowManagerError::TaskbarReservationNotFound => write!(f, "taskbar reservation not found"),
+            WindowManagerError::WinAmbiguous(ref sel, count) => {
+                write!(f, "window selector matched {} windows: {}", count, sel)
+            },
+            WindowManagerError::WinDeleteNotSupported(ref err) => {
+                write!(f, "window does not support WM_DELETE_WINDOW: {}", err)
+            },
+            WindowManagerError::WinNotFound(ref sel) => write!(f, "no window matched selector: {}", sel),
         }
     }
 }
@@ -53,6 +91,7 @@ pub enum ErrorWrapper {
     Connect(x11rb::errors::ConnectError),
     Connection(x11rb::errors::ConnectionError),
     Reply(x11rb::errors::ReplyError),
+    ReplyOrId(x11rb::errors::ReplyOrIdError),
 }
 impl ErrorWrapper {
     /// Implemented directly on the `Error` type to reduce casting required
@@ -86,6 +125,7 @@ impl fmt::Display for ErrorWrapper {
             ErrorWrapper::Connect(ref err) => write!(f, "{}", err),
             ErrorWrapper::Connection(ref err) => write!(f, "{}", err),
             ErrorWrapper::Reply(ref err) => write!(f, "{}", err),
+            ErrorWrapper::ReplyOrId(ref err) => write!(f, "{}", err),
         }
     }
 }
@@ -98,6 +138,7 @@ impl AsRef<dyn StdError> for ErrorWrapper {
             ErrorWrapper::Connect(ref err) => err,
             ErrorWrapper::Connection(ref err) => err,
             ErrorWrapper::Reply(ref err) => err,
+            ErrorWrapper::ReplyOrId(ref err) => err,
         }
     }
 }
@@ -110,6 +151,7 @@ impl AsMut<dyn StdError> for ErrorWrapper {
             ErrorWrapper::Connect(ref mut err) => err,
             ErrorWrapper::Connection(ref mut err) => err,
             ErrorWrapper::Reply(ref mut err) => err,
+            ErrorWrapper::ReplyOrId(ref mut err) => err,
         }
     }
 }
@@ -146,6 +188,12 @@ impl From<x11rb::errors::ReplyError> for ErrorWrapper {
     }
 }
 
+impl From<x11rb::errors::ReplyOrIdError> for ErrorWrapper {
+    fn from(err: x11rb::errors::ReplyOrIdError) -> ErrorWrapper {
+        ErrorWrapper::ReplyOrId(err)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
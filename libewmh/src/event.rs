@@ -0,0 +1,220 @@
+//! `Events` provides a blocking stream of typed window manager events. X11 only reports that a
+//! property changed so the root window's `_NET_CLIENT_LIST`, `_NET_ACTIVE_WINDOW`,
+//! `_NET_CURRENT_DESKTOP` and `_NET_WORKAREA` are diffed against their previous values and each
+//! client window is watched for state, desktop, title and geometry changes.
+//!
+//! ### Examples
+//! ```ignore
+//! use libewmh::prelude::*;
+//! let wmcli = WindowManager::connect().unwrap();
+//! for event in wmcli.events().unwrap() {
+//!     println!("{:?}", event.unwrap());
+//! }
+//! ```
+use std::collections::{HashMap, VecDeque};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+use x11rb::protocol::{
+    xproto::{AtomEnum, EventMask},
+    Event,
+};
+
+use crate::{Rect, WinState, WindowManager, WindowManagerResult};
+
+/// WmEvent provides the window manager changes reported by `WindowManager::events`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "kebab-case"))]
+pub enum WmEvent {
+    ActiveWindowChanged { win: Option<u32> },
+    WindowOpened { win: u32 },
+    WindowClosed { win: u32 },
+    DesktopChanged { desktop: u32 },
+    WindowStateChanged { win: u32, state: Vec<WinState> },
+    WindowDesktopChanged { win: u32, desktop: i32 },
+    WindowMoved { win: u32, rect: Rect },
+    TitleChanged { win: u32, name: String },
+    WorkareaChanged { area: Rect },
+}
+
+/// Events provides an iterator over the window manager events blocking until the next one occurs
+pub struct Events<'a> {
+    wm: &'a WindowManager,               // window manager connection
+    active: Option<u32>,                 // last active window
+    desktop: u32,                        // last current desktop
+    work_area: Rect,                     // last current desktop's work area
+    clients: Vec<u32>,                   // last client list
+    geometry: HashMap<u32, Rect>,        // last client geometry
+    names: HashMap<u32, String>,         // last client names
+    states: HashMap<u32, Vec<WinState>>, // last client states
+    desktops: HashMap<u32, i32>,         // last client desktops
+    pending: VecDeque<WmEvent>,          // events derived but not yet returned
+}
+
+impl<'a> Events<'a> {
+    // Select the root window events and snapshot the current state to diff against
+    pub(crate) fn new(wm: &'a WindowManager) -> WindowManagerResult<Self> {
+        wm.select_input(wm.root(), EventMask::PROPERTY_CHANGE | EventMask::SUBSTRUCTURE_NOTIFY)?;
+        let mut events = Events {
+            wm,
+            active: wm.active_win().ok().filter(|x| *x != 0),
+            desktop: wm.current_desktop().unwrap_or(0),
+            work_area: wm.workarea().unwrap_or_default(),
+            clients: vec![],
+            geometry: HashMap::new(),
+            names: HashMap::new(),
+            states: HashMap::new(),
+            desktops: HashMap::new(),
+            pending: VecDeque::new(),
+        };
+        for win in wm.get_windows(false)? {
+            events.track(win.id);
+        }
+        Ok(events)
+    }
+
+    // Start watching the given client window for changes
+    fn track(&mut self, win: u32) {
+        // The window may already be gone in which case there is nothing to watch
+        let _ = self.wm.select_input(win, EventMask::PROPERTY_CHANGE | EventMask::STRUCTURE_NOTIFY);
+        if let Ok((x, y, w, h)) = self.wm.win_geometry(win) {
+            self.geometry.insert(win, Rect::new(x, y, w as i32, h as i32));
+        }
+        if let Ok(name) = self.wm.win_name(win) {
+            self.names.insert(win, name);
+        }
+        if let Ok(state) = self.wm.win_state(win) {
+            self.states.insert(win, state);
+        }
+        if let Ok(desktop) = self.wm.win_desktop(win) {
+            self.desktops.insert(win, desktop);
+        }
+        self.clients.push(win);
+    }
+
+    // Stop watching the given client window
+    fn untrack(&mut self, win: u32) {
+        self.clients.retain(|x| *x != win);
+        self.geometry.remove(&win);
+        self.names.remove(&win);
+        self.states.remove(&win);
+        self.desktops.remove(&win);
+    }
+
+    // Diff the client list queueing opened and closed windows
+    fn diff_clients(&mut self) -> WindowManagerResult<()> {
+        let clients = self.wm.get_windows(false)?.into_iter().map(|x| x.id).collect::<Vec<_>>();
+        for win in self.clients.clone() {
+            if !clients.contains(&win) {
+                self.untrack(win);
+                self.pending.push_back(WmEvent::WindowClosed { win });
+            }
+        }
+        for win in clients {
+            if !self.clients.contains(&win) {
+                self.track(win);
+                self.pending.push_back(WmEvent::WindowOpened { win });
+            }
+        }
+        Ok(())
+    }
+
+    // Queue a work area change if the current desktop's work area differs
+    fn diff_work_area(&mut self) {
+        if let Ok(area) = self.wm.workarea() {
+            if area != self.work_area {
+                self.work_area = area;
+                self.pending.push_back(WmEvent::WorkareaChanged { area });
+            }
+        }
+    }
+
+    // Derive the typed events from the given X event
+    fn process(&mut self, event: Event) -> WindowManagerResult<()> {
+        let wm = self.wm;
+        let atoms = &wm.atoms;
+        match event {
+            Event::PropertyNotify(e) if e.window == self.wm.root() => {
+                if e.atom == atoms._NET_CLIENT_LIST {
+                    self.diff_clients()?;
+                } else if e.atom == atoms._NET_ACTIVE_WINDOW {
+                    let active = self.wm.active_win().ok().filter(|x| *x != 0);
+                    if active != self.active {
+                        self.active = active;
+                        self.pending.push_back(WmEvent::ActiveWindowChanged { win: active });
+                    }
+                } else if e.atom == atoms._NET_CURRENT_DESKTOP {
+                    let desktop = self.wm.current_desktop()?;
+                    if desktop != self.desktop {
+                        self.desktop = desktop;
+                        self.pending.push_back(WmEvent::DesktopChanged { desktop });
+                    }
+                    self.diff_work_area();
+                } else if e.atom == atoms._NET_WORKAREA {
+                    self.diff_work_area();
+                }
+            },
+            Event::PropertyNotify(e) if self.clients.contains(&e.window) => {
+                let win = e.window;
+                if e.atom == atoms._NET_WM_STATE {
+                    // Window managers may rewrite the state without changing it
+                    if let Ok(state) = self.wm.win_state(win) {
+                        if self.states.get(&win) != Some(&state) {
+                            self.states.insert(win, state.clone());
+                            self.pending.push_back(WmEvent::WindowStateChanged { win, state });
+                        }
+                    }
+                } else if e.atom == atoms._NET_WM_DESKTOP {
+                    if let Ok(desktop) = self.wm.win_desktop(win) {
+                        if self.desktops.get(&win) != Some(&desktop) {
+                            self.desktops.insert(win, desktop);
+                            self.pending.push_back(WmEvent::WindowDesktopChanged { win, desktop });
+                        }
+                    }
+                } else if e.atom == atoms._NET_WM_NAME || e.atom == u32::from(AtomEnum::WM_NAME) {
+                    // Clients often set both names at once so only report actual changes
+                    if let Ok(name) = self.wm.win_name(win) {
+                        if self.names.get(&win) != Some(&name) {
+                            self.names.insert(win, name.clone());
+                            self.pending.push_back(WmEvent::TitleChanged { win, name });
+                        }
+                    }
+                }
+            },
+            Event::ConfigureNotify(e) if self.clients.contains(&e.window) => {
+                // The event's coordinates are relative to the window's frame so query the real ones
+                let win = e.window;
+                if let Ok((x, y, w, h)) = self.wm.win_geometry(win) {
+                    let rect = Rect::new(x, y, w as i32, h as i32);
+                    if self.geometry.get(&win) != Some(&rect) {
+                        self.geometry.insert(win, rect);
+                        self.pending.push_back(WmEvent::WindowMoved { win, rect });
+                    }
+                }
+            },
+            _ => trace!("events: ignoring {:?}", event),
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Iterator for Events<'a> {
+    type Item = WindowManagerResult<WmEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(Ok(event));
+            }
+            let event = match self.wm.wait_for_event() {
+                Ok(event) => event,
+                Err(err) => return Some(Err(err)),
+            };
+            if let Err(err) = self.process(event) {
+                return Some(Err(err));
+            }
+        }
+    }
+}
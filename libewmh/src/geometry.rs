@@ -0,0 +1,850 @@
+//! Pure placement maths for shaping and positioning windows within a work area. Everything here is
+//! signed and free of X calls so that it can be unit tested without an X server. Rectangles passed
+//! to and returned from these functions describe the window's outer frame i.e. the client window
+//! plus the frame extents the window manager decorates it with.
+use std::{convert, fmt};
+
+use crate::{Direction, Layout, WinPosition, WinShape, WindowManagerError};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Point provides a signed location in root window coordinates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    /// Create a new point
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+}
+
+/// Size provides signed dimensions such that calculations can't underflow
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Size {
+    pub w: i32,
+    pub h: i32,
+}
+
+impl Size {
+    /// Create a new size
+    pub fn new(w: i32, h: i32) -> Self {
+        Self { w, h }
+    }
+}
+
+/// Insets provides the frame extents the window manager surrounds a window with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Insets {
+    pub left: i32,
+    pub right: i32,
+    pub top: i32,
+    pub bottom: i32,
+}
+
+impl Insets {
+    /// Create new insets
+    pub fn new(left: i32, right: i32, top: i32, bottom: i32) -> Self {
+        Self { left, right, top, bottom }
+    }
+
+    /// Get the combined left and right insets
+    pub fn horz(&self) -> i32 {
+        self.left + self.right
+    }
+
+    /// Get the combined top and bottom insets
+    pub fn vert(&self) -> i32 {
+        self.top + self.bottom
+    }
+}
+
+// Convert from the (left, right, top, bottom) tuple returned by `WindowManager::win_borders`
+impl From<(u32, u32, u32, u32)> for Insets {
+    fn from(val: (u32, u32, u32, u32)) -> Self {
+        Insets::new(val.0 as i32, val.1 as i32, val.2 as i32, val.3 as i32)
+    }
+}
+
+/// Rect provides a signed rectangle in root window coordinates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub w: i32,
+    pub h: i32,
+}
+
+impl Rect {
+    /// Create a new rectangle
+    pub fn new(x: i32, y: i32, w: i32, h: i32) -> Self {
+        Self { x, y, w, h }
+    }
+
+    /// Get the top left corner of the rectangle
+    pub fn origin(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+
+    /// Get the dimensions of the rectangle
+    pub fn size(&self) -> Size {
+        Size::new(self.w, self.h)
+    }
+
+    /// Get the center point of the rectangle
+    pub fn center(&self) -> Point {
+        Point::new(self.x + self.w / 2, self.y + self.h / 2)
+    }
+
+    /// Check if the given point falls within the rectangle
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= self.x && point.x < self.x + self.w && point.y >= self.y && point.y < self.y + self.h
+    }
+
+    /// Grow the rectangle by the given insets e.g. from a client window to its outer frame
+    pub fn outer(&self, insets: &Insets) -> Rect {
+        Rect::new(self.x - insets.left, self.y - insets.top, self.w + insets.horz(), self.h + insets.vert())
+    }
+
+    /// Shrink the rectangle by the given insets e.g. from an outer frame to its client window
+    pub fn inner(&self, insets: &Insets) -> Rect {
+        Rect::new(self.x + insets.left, self.y + insets.top, self.w - insets.horz(), self.h - insets.vert())
+    }
+
+    /// Map the rectangle proportionally from one area to another keeping its relative position
+    /// and size e.g. moving a window between monitors of different resolutions.
+    ///
+    /// ### Arguments
+    /// * `from` - area the rectangle is currently relative to
+    /// * `to` - area the rectangle should be relative to
+    pub fn map(&self, from: &Rect, to: &Rect) -> Rect {
+        let scale = |val: i32, from: i32, to: i32| (val as i64 * to as i64 / from.max(1) as i64) as i32;
+        Rect::new(
+            to.x + scale(self.x - from.x, from.w, to.w),
+            to.y + scale(self.y - from.y, from.h, to.h),
+            scale(self.w, from.w, to.w),
+            scale(self.h, from.h, to.h),
+        )
+    }
+
+    /// Get the overlapping area of the two rectangles if there is any
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let (x1, y1) = (self.x.max(other.x), self.y.max(other.y));
+        let (x2, y2) = ((self.x + self.w).min(other.x + other.w), (self.y + self.h).min(other.y + other.h));
+        if x2 > x1 && y2 > y1 {
+            Some(Rect::new(x1, y1, x2 - x1, y2 - y1))
+        } else {
+            None
+        }
+    }
+}
+
+// Implement format! support using the X geometry notation
+impl fmt::Display for Rect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x{}{:+}{:+}", self.w, self.h, self.x, self.y)
+    }
+}
+
+/// Calculate the outer frame size for the given shape within the work area. Shapes are defined in
+/// terms of the client window so the frame extents are removed before and added back after.
+/// Returns None for shapes that don't resize the window e.g. maximizing is left to the window manager.
+///
+/// ### Arguments
+/// * `area` - work area the window is being shaped within
+/// * `frame` - frame extents surrounding the window
+/// * `current` - current outer frame size of the window
+/// * `shape` - pre-defined shape to calculate
+///
+/// ### Examples
+/// ```
+/// use libewmh::prelude::*;
+/// let area = Rect::new(0, 0, 1920, 1080);
+/// let size = libewmh::geometry::shape(&area, &Insets::default(), Size::new(500, 500), WinShape::Halfw);
+/// assert_eq!(size, Some(Size::new(960, 1080)));
+/// ```
+pub fn shape(area: &Rect, frame: &Insets, current: Size, shape: WinShape) -> Option<Size> {
+    let (bw, bh) = (frame.horz(), frame.vert());
+    let (w, h) = (current.w - bw, current.h - bh); // current client size
+    let (fw, fh) = (area.w - bw, area.h - bh); // full client size
+    let (hw, hh) = (area.w / 2 - bw, area.h / 2 - bh); // half client size
+
+    let (w, h) = match shape {
+        // Grow the existing dimensions by 1% until full size
+        WinShape::Grow => (((w as f32 * 1.01) as i32).min(fw), ((h as f32 * 1.01) as i32).min(fh)),
+
+        // Half width x full height
+        WinShape::Halfw => (hw, fh),
+
+        // Full width x half height
+        WinShape::Halfh => (fw, hh),
+
+        // Half width x half height
+        WinShape::Small => (hw, hh),
+
+        // 3/4 short side x 4x3 sized long size
+        WinShape::Medium => {
+            if area.h < area.w {
+                let h = fh as f32 * 0.75;
+                ((h * 4.0 / 3.0) as i32, h as i32)
+            } else {
+                let w = fw as f32 * 0.75;
+                (w as i32, (w * 4.0 / 3.0) as i32)
+            }
+        },
+
+        // Full short side x 4x3 sized long size
+        WinShape::Large => {
+            if area.h < area.w {
+                ((fh as f32 * 4.0 / 3.0) as i32, fh)
+            } else {
+                (fw, (fw as f32 * 4.0 / 3.0) as i32)
+            }
+        },
+
+        // Shrink the existing dimensions by 1% down to no smaller than 100x100
+        WinShape::Shrink => (((w as f32 * 0.99) as i32).max(100), ((h as f32 * 0.99) as i32).max(100)),
+
+        // User defined fractions or pixels of the work area
+        WinShape::Custom(_, custom) => {
+            let size = custom.size(Size::new(fw, fh), Size::new(w, h))?;
+            (size.w, size.h)
+        },
+
+        // Don't change anything by default
+        _ => return None,
+    };
+    Some(Size::new(w.max(1) + bw, h.max(1) + bh))
+}
+
+/// Calculate the outer frame location for the given position within the work area. Positions that
+/// only align along one axis e.g. `left` leave the other axis as None.
+///
+/// ### Arguments
+/// * `area` - work area the window is being positioned within
+/// * `size` - outer frame size of the window
+/// * `pos` - pre-defined position to calculate
+///
+/// ### Examples
+/// ```
+/// use libewmh::prelude::*;
+/// let area = Rect::new(0, 0, 1920, 1080);
+/// let (x, y) = libewmh::geometry::position(&area, Size::new(960, 1080), WinPosition::Right);
+/// assert_eq!((x, y), (Some(960), None));
+/// ```
+pub fn position(area: &Rect, size: Size, pos: WinPosition) -> (Option<i32>, Option<i32>) {
+    // Windows larger than the work area are aligned to its top left rather than pushed off of it
+    let cx = area.x + ((area.w - size.w) / 2).max(0); // center x
+    let cy = area.y + ((area.h - size.h) / 2).max(0); // center y
+    let rx = area.x + (area.w - size.w).max(0); // right x
+    let by = area.y + (area.h - size.h).max(0); // bottom y
+    let (lx, ty) = (area.x, area.y); // left x, top y
+
+    match pos {
+        WinPosition::Center => (Some(cx), Some(cy)),
+        WinPosition::Left => (Some(lx), None),
+        WinPosition::Right => (Some(rx), None),
+        WinPosition::Top => (None, Some(ty)),
+        WinPosition::Bottom => (None, Some(by)),
+        WinPosition::TopLeft => (Some(lx), Some(ty)),
+        WinPosition::TopRight => (Some(rx), Some(ty)),
+        WinPosition::BottomLeft => (Some(lx), Some(by)),
+        WinPosition::BottomRight => (Some(rx), Some(by)),
+        WinPosition::LeftCenter => (Some(lx), Some(cy)),
+        WinPosition::RightCenter => (Some(rx), Some(cy)),
+        WinPosition::TopCenter => (Some(cx), Some(ty)),
+        WinPosition::BottomCenter => (Some(cx), Some(by)),
+
+        // User defined positions align within the work area inset by their margin
+        WinPosition::Custom(_, custom) => {
+            let m = custom.margin;
+            position(&area.inner(&Insets::new(m, m, m, m)), size, custom.align)
+        },
+    }
+}
+
+/// Calculate the outer frame rectangle the window should have after applying the optional shape
+/// and position. Reshaping without a position keeps the window centered where it currently is
+/// while positions aligning along a single axis keep the window's origin on the other axis.
+///
+/// ### Arguments
+/// * `area` - work area the window is being placed within
+/// * `frame` - frame extents surrounding the window
+/// * `current` - current outer frame rectangle of the window
+/// * `shape` - optional pre-defined shape to calculate
+/// * `pos` - optional pre-defined position to calculate
+///
+/// ### Examples
+/// ```
+/// use libewmh::prelude::*;
+/// let area = Rect::new(0, 0, 1920, 1080);
+/// let current = Rect::new(100, 100, 500, 500);
+/// let rect = libewmh::geometry::target(&area, &Insets::default(), &current, None, Some(WinPosition::Center));
+/// assert_eq!(rect, Rect::new(710, 290, 500, 500));
+/// ```
+pub fn target(
+    area: &Rect, frame: &Insets, current: &Rect, shape: Option<WinShape>, pos: Option<WinPosition>,
+) -> Rect {
+    let size = shape.and_then(|x| self::shape(area, frame, current.size(), x)).unwrap_or(current.size());
+    match pos {
+        // Axes the position doesn't align along keep the window's current origin
+        Some(pos) => {
+            let (x, y) = position(area, size, pos);
+            Rect::new(x.unwrap_or(current.x), y.unwrap_or(current.y), size.w, size.h)
+        },
+        None => {
+            let center = current.center();
+            Rect::new(center.x - size.w / 2, center.y - size.h / 2, size.w, size.h)
+        },
+    }
+}
+
+/// Length provides a dimension or offset in either pixels or as a percentage of the work area
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    Pixels(i32),
+    Percent(f32),
+}
+
+impl Length {
+    /// Resolve the length into pixels against the given total length
+    pub fn resolve(&self, total: i32) -> i32 {
+        match *self {
+            Length::Pixels(x) => x,
+            Length::Percent(x) => (total as f32 * x / 100.0).round() as i32,
+        }
+    }
+}
+
+// Implement format! support
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Length::Pixels(x) => write!(f, "{}", x),
+            Length::Percent(x) => write!(f, "{}%", x),
+        }
+    }
+}
+
+// Convert from &str to Length e.g. `1200` or `50%`
+impl convert::TryFrom<&str> for Length {
+    type Error = WindowManagerError;
+
+    fn try_from(val: &str) -> Result<Self, Self::Error> {
+        let err = || WindowManagerError::InvalidGeometry(val.to_string());
+        match val.strip_suffix('%') {
+            Some(x) => Ok(Length::Percent(x.parse::<f32>().ok().filter(|x| x.is_finite()).ok_or_else(err)?)),
+            None => Ok(Length::Pixels(val.parse::<i32>().map_err(|_| err())?)),
+        }
+    }
+}
+
+/// Offset provides a location along one axis measured from either the near or the far edge of the
+/// work area i.e. `+10` is 10 pixels from the left or top while `-10` is 10 pixels from the right or
+/// bottom.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Offset {
+    pub far: bool,
+    pub len: Length,
+}
+
+impl Offset {
+    /// Resolve the offset into an absolute location for a frame of the given size
+    ///
+    /// ### Arguments
+    /// * `start` - location of the work area's near edge
+    /// * `total` - length of the work area along the axis
+    /// * `size` - outer frame length of the window along the axis
+    pub fn resolve(&self, start: i32, total: i32, size: i32) -> i32 {
+        let len = self.len.resolve(total);
+        if self.far {
+            start.saturating_add(total).saturating_sub(size).saturating_sub(len)
+        } else {
+            start.saturating_add(len)
+        }
+    }
+}
+
+// Implement format! support
+impl fmt::Display for Offset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", if self.far { "-" } else { "+" }, self.len)
+    }
+}
+
+/// GeometrySpec provides X style geometry strings extended with percentages of the work area
+///
+/// * `1200x800` - resize the window's client area leaving it where it is
+/// * `+0+0` - move the window's frame to the top left of the work area
+/// * `1200x800-0-0` - resize and anchor the window's frame to the bottom right of the work area
+/// * `50%x100%+50%+0` - right half of the work area, percentage sizes include the window frame
+///
+/// ### Examples
+/// ```
+/// use libewmh::prelude::*;
+/// let spec = GeometrySpec::try_from("50%x100%+50%+0").unwrap();
+/// let rect = spec.resolve(&Rect::new(0, 0, 1920, 1080), &Insets::default(), &Rect::new(0, 0, 500, 500));
+/// assert_eq!(rect, Rect::new(960, 0, 960, 1080));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeometrySpec {
+    pub size: Option<(Length, Length)>,
+    pub offset: Option<(Offset, Offset)>,
+}
+
+impl GeometrySpec {
+    /// Resolve the geometry into the outer frame rectangle the window should have. Pixel sizes are
+    /// the client size like X geometry while percentage sizes are of the work area including the
+    /// frame such that e.g. two `50%` windows fill the work area exactly.
+    ///
+    /// ### Arguments
+    /// * `area` - work area percentages and offsets are relative to
+    /// * `frame` - frame extents surrounding the window
+    /// * `current` - current outer frame rectangle of the window used for anything not specified
+    pub fn resolve(&self, area: &Rect, frame: &Insets, current: &Rect) -> Rect {
+        let dim = |len: Length, total: i32, border: i32| match len {
+            Length::Pixels(x) => x.max(1).saturating_add(border),
+            Length::Percent(_) => len.resolve(total).max(1 + border),
+        };
+        let (w, h) = match self.size {
+            Some((w, h)) => (dim(w, area.w, frame.horz()), dim(h, area.h, frame.vert())),
+            None => (current.w, current.h),
+        };
+        let (x, y) = match self.offset {
+            Some((x, y)) => (x.resolve(area.x, area.w, w), y.resolve(area.y, area.h, h)),
+            None => (current.x, current.y),
+        };
+
+        // Keep huge values within what X11 can represent so the frame can still be added and
+        // removed without overflowing
+        let pos = |x: i32| x.clamp(i16::MIN as i32, i16::MAX as i32);
+        let len = |x: i32| x.clamp(1, u16::MAX as i32);
+        Rect::new(pos(x), pos(y), len(w), len(h))
+    }
+}
+
+// Implement format! support using the same notation it was parsed from
+impl fmt::Display for GeometrySpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some((w, h)) = self.size {
+            write!(f, "{}x{}", w, h)?;
+        }
+        if let Some((x, y)) = self.offset {
+            write!(f, "{}{}", x, y)?;
+        }
+        Ok(())
+    }
+}
+
+// Convert from &str to GeometrySpec i.e. `[<W>x<H>][{+-}<X>{+-}<Y>]`
+impl convert::TryFrom<&str> for GeometrySpec {
+    type Error = WindowManagerError;
+
+    fn try_from(val: &str) -> Result<Self, Self::Error> {
+        let err = || WindowManagerError::InvalidGeometry(val.to_string());
+        let (size, offsets) = val.split_at(val.find(['+', '-']).unwrap_or(val.len()));
+
+        // Size is given as <W>x<H>
+        let size = match size.split_once(['x', 'X']) {
+            Some((w, h)) => {
+                let (w, h) = (Length::try_from(w), Length::try_from(h));
+                Some((w.map_err(|_| err())?, h.map_err(|_| err())?))
+            },
+            None if size.is_empty() => None,
+            None => return Err(err()),
+        };
+
+        // Offsets are given as an edge sign followed by an optionally signed length e.g. `-0+-10`
+        let mut parts = vec![];
+        let mut rest = offsets;
+        while let Some(sign) = rest.chars().next() {
+            // Skip the length's first character which may be its own sign, splitting on characters
+            // rather than bytes as the input may not be ASCII
+            let body = &rest[sign.len_utf8()..];
+            let skip = body.chars().next().map_or(0, char::len_utf8);
+            let end = body[skip..].find(['+', '-']).map_or(body.len(), |i| i + skip);
+            let len = Length::try_from(&body[..end]).map_err(|_| err())?;
+            parts.push(Offset { far: sign == '-', len });
+            rest = &body[end..];
+        }
+        let offset = match parts[..] {
+            [] => None,
+            [x, y] => Some((x, y)),
+            _ => return Err(err()),
+        };
+
+        if size.is_none() && offset.is_none() {
+            return Err(err());
+        }
+        Ok(GeometrySpec { size, offset })
+    }
+}
+
+// Convert from a String to a GeometrySpec
+impl convert::TryFrom<String> for GeometrySpec {
+    type Error = WindowManagerError;
+
+    fn try_from(val: String) -> Result<Self, Self::Error> {
+        GeometrySpec::try_from(val.as_str())
+    }
+}
+
+/// Grid provides gTile/Divvy style placement dividing the work area into columns and rows and
+/// spanning the window over one or more cells. Cell edges are rounded consistently such that
+/// neighbouring cells tile the work area exactly.
+///
+/// ### Examples
+/// ```
+/// use libewmh::prelude::*;
+/// let grid = Grid::new(3, 2).cell(0, 0).span(2, 1);
+/// assert_eq!(grid.rect(&Rect::new(0, 0, 1920, 1080)).unwrap(), Rect::new(0, 0, 1280, 540));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Grid {
+    pub cols: u32,
+    pub rows: u32,
+    pub col: u32,
+    pub row: u32,
+    pub col_span: u32,
+    pub row_span: u32,
+}
+
+impl Grid {
+    /// Create a new grid with the given number of columns and rows placing the window in the
+    /// top left cell.
+    ///
+    /// ### Arguments
+    /// * `cols` - number of columns to divide the work area into
+    /// * `rows` - number of rows to divide the work area into
+    pub fn new(cols: u32, rows: u32) -> Self {
+        Self { cols, rows, col: 0, row: 0, col_span: 1, row_span: 1 }
+    }
+
+    /// Set the zero based column and row of the cell the window should start in
+    pub fn cell(mut self, col: u32, row: u32) -> Self {
+        self.col = col;
+        self.row = row;
+        self
+    }
+
+    /// Set the number of columns and rows the window should span
+    pub fn span(mut self, cols: u32, rows: u32) -> Self {
+        self.col_span = cols;
+        self.row_span = rows;
+        self
+    }
+
+    /// Calculate the outer frame rectangle covering the grid cells within the work area
+    ///
+    /// ### Arguments
+    /// * `area` - work area to divide into cells
+    pub fn rect(&self, area: &Rect) -> Result<Rect, WindowManagerError> {
+        if self.cols == 0
+            || self.rows == 0
+            || self.col_span == 0
+            || self.row_span == 0
+            || self.col_span > self.cols.saturating_sub(self.col)
+            || self.row_span > self.rows.saturating_sub(self.row)
+        {
+            return Err(WindowManagerError::InvalidGrid(self.to_string()));
+        }
+        let edge = |start: i32, total: i32, i: u32, n: u32| start + (total as i64 * i as i64 / n as i64) as i32;
+        let (x, y) = (edge(area.x, area.w, self.col, self.cols), edge(area.y, area.h, self.row, self.rows));
+        let x2 = edge(area.x, area.w, self.col + self.col_span, self.cols);
+        let y2 = edge(area.y, area.h, self.row + self.row_span, self.rows);
+        Ok(Rect::new(x, y, x2 - x, y2 - y))
+    }
+}
+
+// Implement format! support using the cli notation
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "grid {}x{} cell {},{} span {}x{}",
+            self.cols, self.rows, self.col, self.row, self.col_span, self.row_span
+        )
+    }
+}
+
+/// Find the nearest rectangle in the given direction from a rectangle returning its index. Only
+/// rectangles whose center lies beyond the center of `from` are considered and those overlapping
+/// `from` across the direction of travel are preferred e.g. a window directly to the left over a
+/// closer one that is up and to the left.
+///
+/// ### Arguments
+/// * `from` - rectangle to look from e.g. the active window
+/// * `rects` - rectangles to choose from
+/// * `dir` - direction to look in
+///
+/// ### Examples
+/// ```
+/// use libewmh::prelude::*;
+/// let rects = [Rect::new(960, 0, 960, 540), Rect::new(960, 540, 960, 540)];
+/// let from = Rect::new(0, 0, 960, 1080);
+/// assert_eq!(libewmh::geometry::nearest(&from, &rects, Direction::Right), Some(0));
+/// ```
+pub fn nearest(from: &Rect, rects: &[Rect], dir: Direction) -> Option<usize> {
+    let center = from.center();
+    let overlaps = |a: i32, a_len: i32, b: i32, b_len: i32| a < b + b_len && b < a + a_len;
+    rects
+        .iter()
+        .enumerate()
+        .filter_map(|(i, rect)| {
+            // Distance along the direction of travel and across it along with any overlap across it
+            let c = rect.center();
+            let (dist, across, overlap) = match dir {
+                Direction::Left => (center.x - c.x, c.y - center.y, overlaps(from.y, from.h, rect.y, rect.h)),
+                Direction::Right => (c.x - center.x, c.y - center.y, overlaps(from.y, from.h, rect.y, rect.h)),
+                Direction::Up => (center.y - c.y, c.x - center.x, overlaps(from.x, from.w, rect.x, rect.w)),
+                Direction::Down => (c.y - center.y, c.x - center.x, overlaps(from.x, from.w, rect.x, rect.w)),
+            };
+            Some((!overlap, dist, across.abs(), i)).filter(|_| dist > 0)
+        })
+        .min()
+        .map(|(.., i)| i)
+}
+
+// Pixels each cascaded window is offset from the previous one
+const CASCADE_STEP: i32 = 32;
+
+/// Calculate the outer frame rectangles tiling the given number of windows within the work area.
+/// The master-stack layout gives the first window the master column and stacks the rest in rows
+/// beside it, monocle gives every window the whole work area and cascade overlaps windows of two
+/// thirds of the work area each offset from the previous one.
+///
+/// ### Arguments
+/// * `area` - work area to tile the windows within
+/// * `count` - number of windows to tile
+/// * `layout` - layout to arrange the windows in
+/// * `ratio` - fraction of the work area's width given to the master column
+/// * `gap` - pixels to leave between the windows and around the edges of the work area
+///
+/// ### Examples
+/// ```
+/// use libewmh::prelude::*;
+/// let rects = libewmh::geometry::tile(&Rect::new(0, 0, 1920, 1080), 2, Layout::Columns, 0.5, 0);
+/// assert_eq!(rects, vec![Rect::new(0, 0, 960, 1080), Rect::new(960, 0, 960, 1080)]);
+/// ```
+pub fn tile(area: &Rect, count: usize, layout: Layout, ratio: f32, gap: i32) -> Vec<Rect> {
+    let cell = |col: (f64, f64), row: (f64, f64)| {
+        let (x, w) = segment(area.x, area.w, gap, col);
+        let (y, h) = segment(area.y, area.h, gap, row);
+        Rect::new(x, y, w, h)
+    };
+    let nth = |i: usize, n: usize| (i as f64 / n as f64, (i + 1) as f64 / n as f64);
+    let full = (0.0, 1.0);
+    match layout {
+        _ if count == 0 => vec![],
+        Layout::MasterStack if count > 1 => {
+            let ratio = ratio.clamp(0.0, 1.0) as f64;
+            let mut rects = vec![cell((0.0, ratio), full)];
+            rects.extend((0..count - 1).map(|i| cell((ratio, 1.0), nth(i, count - 1))));
+            rects
+        },
+        Layout::MasterStack | Layout::Monocle => vec![cell(full, full); count],
+        Layout::Columns => (0..count).map(|i| cell(nth(i, count), full)).collect(),
+        Layout::Rows => (0..count).map(|i| cell(full, nth(i, count))).collect(),
+        Layout::Grid => {
+            // The last row shares its width between however many windows are left over
+            let cols = (count as f64).sqrt().ceil() as usize;
+            let rows = count.div_ceil(cols);
+            (0..count)
+                .map(|i| {
+                    let (row, col) = (i / cols, i % cols);
+                    cell(nth(col, cols.min(count - row * cols)), nth(row, rows))
+                })
+                .collect()
+        },
+        Layout::Cascade => {
+            // Wrap back around to the top left once the windows would run off the work area
+            let inner = area.inner(&Insets::new(gap, gap, gap, gap));
+            let (w, h) = ((inner.w * 2 / 3).max(1), (inner.h * 2 / 3).max(1));
+            let steps = ((inner.w - w).min(inner.h - h) / CASCADE_STEP + 1).max(1) as usize;
+            (0..count)
+                .map(|i| {
+                    let offset = (i % steps) as i32 * CASCADE_STEP;
+                    Rect::new(inner.x + offset, inner.y + offset, w, h)
+                })
+                .collect()
+        },
+    }
+}
+
+// Get the start and length of the segment between the given fractions of the span leaving `gap`
+// pixels between neighbouring segments and around the outside. Neighbouring segments share edges
+// so that rounding never leaves uneven gaps.
+fn segment(start: i32, len: i32, gap: i32, (from, to): (f64, f64)) -> (i32, i32) {
+    let edge = |f: f64| start + gap + ((len - gap) as f64 * f).round() as i32;
+    (edge(from), (edge(to) - edge(from) - gap).max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CustomPosition;
+
+    #[test]
+    fn test_map() {
+        let from = Rect::new(0, 0, 1920, 1080);
+        let to = Rect::new(1920, 0, 3840, 2160);
+        assert_eq!(Rect::new(960, 540, 480, 270).map(&from, &to), Rect::new(3840, 1080, 960, 540));
+        assert_eq!(Rect::new(3840, 1080, 960, 540).map(&to, &from), Rect::new(960, 540, 480, 270));
+    }
+
+    #[test]
+    fn test_shape() {
+        let area = Rect::new(1920, 30, 1920, 1050);
+        let frame = Insets::new(2, 2, 30, 2);
+        let current = Size::new(504, 532);
+        assert_eq!(shape(&area, &frame, current, WinShape::Halfw), Some(Size::new(960, 1050)));
+        assert_eq!(shape(&area, &frame, current, WinShape::Halfh), Some(Size::new(1920, 525)));
+        assert_eq!(shape(&area, &frame, current, WinShape::Small), Some(Size::new(960, 525)));
+        assert_eq!(shape(&area, &frame, current, WinShape::Grow), Some(Size::new(509, 537)));
+        assert_eq!(shape(&area, &frame, current, WinShape::Shrink), Some(Size::new(499, 527)));
+        assert_eq!(shape(&area, &frame, current, WinShape::Max), None);
+
+        // Frames larger than the work area must not underflow
+        let tiny = Rect::new(0, 0, 10, 10);
+        assert_eq!(shape(&tiny, &frame, current, WinShape::Small), Some(Size::new(5, 33)));
+        assert_eq!(shape(&tiny, &frame, current, WinShape::Grow), Some(Size::new(10, 33)));
+    }
+
+    #[test]
+    fn test_position() {
+        let area = Rect::new(1920, 30, 1920, 1050);
+        let size = Size::new(500, 500);
+        assert_eq!(position(&area, size, WinPosition::Center), (Some(2630), Some(305)));
+        assert_eq!(position(&area, size, WinPosition::Left), (Some(1920), None));
+        assert_eq!(position(&area, size, WinPosition::BottomRight), (Some(3340), Some(580)));
+        assert_eq!(position(&area, Size::new(4000, 2000), WinPosition::BottomRight), (Some(1920), Some(30)));
+
+        let custom = CustomPosition { align: WinPosition::BottomRight, margin: 20 };
+        let pos = WinPosition::Custom("corner".to_string(), Box::new(custom));
+        assert_eq!(position(&area, size, pos), (Some(3320), Some(560)));
+    }
+
+    #[test]
+    fn test_target() {
+        let area = Rect::new(0, 0, 1920, 1080);
+        let frame = Insets::default();
+        let current = Rect::new(101, 100, 500, 500);
+        assert_eq!(target(&area, &frame, &current, None, None), current);
+        assert_eq!(target(&area, &frame, &current, Some(WinShape::Small), None), Rect::new(-129, 80, 960, 540));
+        assert_eq!(
+            target(&area, &frame, &current, Some(WinShape::Halfw), Some(WinPosition::Right)),
+            Rect::new(960, 100, 960, 1080)
+        );
+        assert_eq!(target(&area, &frame, &current, None, Some(WinPosition::Top)), Rect::new(101, 0, 500, 500));
+    }
+
+    #[test]
+    fn test_geometry_spec() {
+        let area = Rect::new(1920, 30, 1920, 1050);
+        let frame = Insets::new(2, 2, 30, 2);
+        let current = Rect::new(2000, 100, 504, 532);
+        let resolve = |x: &str| GeometrySpec::try_from(x).unwrap().resolve(&area, &frame, &current);
+        assert_eq!(resolve("1200x800"), Rect::new(2000, 100, 1204, 832));
+        assert_eq!(resolve("+0+0"), Rect::new(1920, 30, 504, 532));
+        assert_eq!(resolve("1200x800+0+0"), Rect::new(1920, 30, 1204, 832));
+        assert_eq!(resolve("50%x100%+50%+0"), Rect::new(2880, 30, 960, 1050));
+        assert_eq!(resolve("800x600-0-0"), Rect::new(3036, 448, 804, 632));
+        assert_eq!(resolve("+-10-10"), Rect::new(1910, 538, 504, 532));
+
+        for x in ["1200x800", "+0+0", "50%x100%+50%+0", "800x600-0-0", "+-10-10"] {
+            assert_eq!(GeometrySpec::try_from(x).unwrap().to_string(), x);
+        }
+        for x in ["", "1200", "1200x", "x800", "+0", "+0+0+0", "1200x800+0", "axb", "+%+0", "50%%x1"] {
+            assert!(GeometrySpec::try_from(x).is_err(), "{}", x);
+        }
+
+        // Huge values are kept within X11's limits rather than overflowing
+        assert_eq!(resolve("+1e30%+0"), Rect::new(i16::MAX as i32, 30, 504, 532));
+        assert_eq!(resolve("2147483647x10-0+0"), Rect::new(i16::MIN as i32, 30, u16::MAX as i32, 42));
+        assert_eq!(resolve("1x1+2147483647-2147483647").x, i16::MAX as i32);
+
+        // Non-ASCII input is rejected rather than splitting a character
+        for x in ["100x100+é", "50%x50%-é0", "+é+0"] {
+            assert!(GeometrySpec::try_from(x).is_err(), "{}", x);
+        }
+    }
+
+    #[test]
+    fn test_grid() {
+        let area = Rect::new(1920, 30, 1920, 1050);
+        assert_eq!(Grid::new(3, 2).rect(&area).unwrap(), Rect::new(1920, 30, 640, 525));
+        assert_eq!(Grid::new(3, 2).cell(1, 1).span(2, 1).rect(&area).unwrap(), Rect::new(2560, 555, 1280, 525));
+
+        // Neighbouring cells share edges even when the area doesn't divide evenly
+        let area = Rect::new(0, 0, 1000, 1000);
+        let left = Grid::new(3, 1).rect(&area).unwrap();
+        let middle = Grid::new(3, 1).cell(1, 0).rect(&area).unwrap();
+        let right = Grid::new(3, 1).cell(2, 0).rect(&area).unwrap();
+        assert_eq!((left.x + left.w, middle.x + middle.w, right.x + right.w), (middle.x, right.x, 1000));
+
+        assert!(Grid::new(0, 2).rect(&area).is_err());
+        assert!(Grid::new(3, 2).cell(2, 0).span(2, 1).rect(&area).is_err());
+        assert!(Grid::new(3, 2).span(0, 1).rect(&area).is_err());
+        assert!(Grid::new(3, 2).cell(u32::MAX, 0).span(2, 1).rect(&area).is_err());
+        assert!(Grid::new(3, 2).cell(0, 1).span(1, u32::MAX).rect(&area).is_err());
+    }
+
+    #[test]
+    fn test_nearest() {
+        // Two windows on the left half, one top right and one small one bottom right
+        let from = Rect::new(0, 0, 960, 540);
+        let rects = [
+            Rect::new(0, 540, 960, 540),
+            Rect::new(960, 0, 960, 540),
+            Rect::new(1000, 600, 200, 200),
+            Rect::new(0, 0, 960, 540),
+        ];
+        assert_eq!(nearest(&from, &rects, Direction::Down), Some(0));
+        assert_eq!(nearest(&from, &rects, Direction::Right), Some(1));
+        assert_eq!(nearest(&from, &rects, Direction::Left), None);
+        assert_eq!(nearest(&from, &rects, Direction::Up), None);
+
+        // Overlapping windows are preferred over closer ones off to the side
+        let from = Rect::new(1000, 600, 200, 200);
+        assert_eq!(nearest(&from, &rects, Direction::Left), Some(0));
+        assert_eq!(nearest(&from, &rects, Direction::Up), Some(1));
+    }
+
+    #[test]
+    fn test_tile() {
+        let area = Rect::new(0, 30, 1000, 600);
+        assert_eq!(tile(&area, 0, Layout::Grid, 0.5, 0), vec![]);
+        assert_eq!(tile(&area, 1, Layout::MasterStack, 0.6, 10), vec![Rect::new(10, 40, 980, 580)]);
+        assert_eq!(
+            tile(&area, 3, Layout::MasterStack, 0.6, 10),
+            vec![Rect::new(10, 40, 584, 580), Rect::new(604, 40, 386, 285), Rect::new(604, 335, 386, 285)]
+        );
+        assert_eq!(
+            tile(&area, 2, Layout::Rows, 0.5, 0),
+            vec![Rect::new(0, 30, 1000, 300), Rect::new(0, 330, 1000, 300)]
+        );
+        assert_eq!(tile(&area, 2, Layout::Monocle, 0.5, 0), vec![area, area]);
+
+        // Three windows fill a 2x2 grid with the last one spanning the bottom row
+        assert_eq!(
+            tile(&area, 3, Layout::Grid, 0.5, 0),
+            vec![Rect::new(0, 30, 500, 300), Rect::new(500, 30, 500, 300), Rect::new(0, 330, 1000, 300)]
+        );
+
+        // Cascaded windows wrap back to the top left before running off the work area
+        let cascade = tile(&area, 8, Layout::Cascade, 0.5, 0);
+        assert_eq!((cascade[1], cascade[7]), (Rect::new(32, 62, 666, 400), Rect::new(0, 30, 666, 400)));
+
+        // Gaps between columns match the gaps around the edges
+        let cols = tile(&area, 3, Layout::Columns, 0.5, 10);
+        assert_eq!((cols[0].x, cols[1].x - (cols[0].x + cols[0].w), 1000 - (cols[2].x + cols[2].w)), (10, 10, 10));
+    }
+}
@@ -12,13 +12,28 @@
 //! be shaped and positioned on the screen in an ergonomic way; however `libewmh` could be used
 //! for a variety of reasons.
 mod atoms;
+pub mod config;
+pub mod desktop;
 mod error;
+mod event;
+pub mod geometry;
 mod model;
+pub mod rule;
+mod selector;
+pub mod snapshot;
 pub mod window;
 mod wm;
+pub use config::{Config, CustomPosition, CustomShape};
 pub use error::*;
+pub use event::{Events, WmEvent};
+pub use geometry::{GeometrySpec, Grid, Insets, Length, Offset, Point, Rect, Size};
 pub use model::*;
-pub use wm::WindowManager;
+pub use rule::Rule;
+pub use selector::*;
+pub use snapshot::{Snapshot, SnapshotWin};
+pub use wm::{WindowManager, ALL_DESKTOPS};
+#[allow(deprecated)]
+pub use wm::{WindowStateAction, WINDOW_STATE_ACTION_ADD, WINDOW_STATE_ACTION_REMOVE};
 
 /// All essential symbols in a simple consumable form
 ///
@@ -27,11 +42,10 @@ pub use wm::WindowManager;
 /// use libewmh::prelude::*;
 /// ```
 pub mod prelude {
+    pub use crate::window::WinOpt;
     pub use crate::*;
 }
 
-/// Window option provides an ergonomic way to manipulate a window
-
 #[cfg(test)]
 mod tests {
     #[test]
@@ -1,8 +1,14 @@
 use std::{convert, fmt};
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use x11rb::protocol::xproto;
 
-use crate::{atoms::AtomCollection, WindowManagerError, WindowManagerResult};
+use crate::{
+    atoms::AtomCollection,
+    config::{self, CustomPosition, CustomShape},
+    Rect, WindowManagerError, WindowManagerResult,
+};
 
 /// WinGravity
 /// Gravity is defined as the lower byte of the move resize flags 32bit value
@@ -16,9 +22,7 @@ pub enum WinGravity {
 // Implement format! support
 impl fmt::Display for WinGravity {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            _ => write!(f, "{}", format!("{:?}", self).to_lowercase()),
-        }
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
     }
 }
 
@@ -43,6 +47,8 @@ impl From<WinGravity> for u32 {
 /// WinPosition provides a number of pre-defined positions on the screen to quickly and easily
 /// move the window to taking into account borders and taskbars automatically.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 pub enum WinPosition {
     Center,
     Left,
@@ -57,12 +63,14 @@ pub enum WinPosition {
     RightCenter,
     TopCenter,
     BottomCenter,
+    Custom(String, Box<CustomPosition>),
 }
 
 // Implement format! support
 impl fmt::Display for WinPosition {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            WinPosition::Custom(name, _) => write!(f, "{}", name),
             _ => write!(f, "{}", format!("{:?}", self).to_lowercase()),
         }
     }
@@ -87,7 +95,10 @@ impl convert::TryFrom<&str> for WinPosition {
             "right-center" => Ok(WinPosition::RightCenter),
             "top-center" => Ok(WinPosition::TopCenter),
             "bottom-center" => Ok(WinPosition::BottomCenter),
-            _ => Err(WindowManagerError::InvalidWinPosition(val.to_string()).into()),
+            _ => match config::get().positions.get(val) {
+                Some(pos) => Ok(WinPosition::Custom(val.to_string(), Box::new(pos.clone()))),
+                None => Err(WindowManagerError::InvalidWinPosition(val.to_string())),
+            },
         }
     }
 }
@@ -104,6 +115,8 @@ impl convert::TryFrom<String> for WinPosition {
 /// WinShape provides a number of pre-defined shapes to manipulate the window into, taking into
 /// account borders and taskbars automatically.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "String", try_from = "String"))]
 pub enum WinShape {
     Grow,
     Max,
@@ -115,17 +128,26 @@ pub enum WinShape {
     Shrink,
     Square,
     UnMax,
+    Custom(String, CustomShape),
 }
 
 // Implement format! support
 impl fmt::Display for WinShape {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            WinShape::Custom(name, _) => write!(f, "{}", name),
             _ => write!(f, "{}", format!("{:?}", self).to_lowercase()),
         }
     }
 }
 
+// Convert from a Shape to the name it's parsed from
+impl From<WinShape> for String {
+    fn from(val: WinShape) -> Self {
+        val.to_string()
+    }
+}
+
 // Convert from &str to Shape
 impl convert::TryFrom<&str> for WinShape {
     type Error = WindowManagerError;
@@ -141,7 +163,10 @@ impl convert::TryFrom<&str> for WinShape {
             "large" => Ok(WinShape::Large),
             "shrink" => Ok(WinShape::Shrink),
             "unmax" => Ok(WinShape::UnMax),
-            _ => Err(WindowManagerError::InvalidWinShape(val.to_string()).into()),
+            _ => match config::get().shapes.get(val) {
+                Some(shape) => Ok(WinShape::Custom(val.to_string(), shape.clone())),
+                None => Err(WindowManagerError::InvalidWinShape(val.to_string())),
+            },
         }
     }
 }
@@ -155,9 +180,102 @@ impl convert::TryFrom<String> for WinShape {
     }
 }
 
+/// Direction provides the directions to look for a neighbouring window in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+// Implement format! support
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+// Convert from &str to Direction
+impl convert::TryFrom<&str> for Direction {
+    type Error = WindowManagerError;
+
+    fn try_from(val: &str) -> Result<Self, Self::Error> {
+        match val.to_lowercase().as_ref() {
+            "left" => Ok(Direction::Left),
+            "right" => Ok(Direction::Right),
+            "up" => Ok(Direction::Up),
+            "down" => Ok(Direction::Down),
+            _ => Err(WindowManagerError::InvalidDirection(val.to_string())),
+        }
+    }
+}
+
+// Convert from a String to a Direction
+impl convert::TryFrom<String> for Direction {
+    type Error = WindowManagerError;
+
+    fn try_from(val: String) -> Result<Self, Self::Error> {
+        Direction::try_from(val.as_str())
+    }
+}
+
+/// Layout provides the ways the windows of a desktop can be tiled within the work area
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Layout {
+    MasterStack,
+    Columns,
+    Rows,
+    Grid,
+    Monocle,
+    Cascade,
+}
+
+// Implement format! support
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Layout::MasterStack => write!(f, "master-stack"),
+            _ => write!(f, "{}", format!("{:?}", self).to_lowercase()),
+        }
+    }
+}
+
+// Convert from &str to Layout
+impl convert::TryFrom<&str> for Layout {
+    type Error = WindowManagerError;
+
+    fn try_from(val: &str) -> Result<Self, Self::Error> {
+        match val.to_lowercase().as_ref() {
+            "master-stack" | "tile" => Ok(Layout::MasterStack),
+            "columns" => Ok(Layout::Columns),
+            "rows" => Ok(Layout::Rows),
+            "grid" => Ok(Layout::Grid),
+            "monocle" => Ok(Layout::Monocle),
+            "cascade" => Ok(Layout::Cascade),
+            _ => Err(WindowManagerError::InvalidLayout(val.to_string())),
+        }
+    }
+}
+
+// Convert from a String to a Layout
+impl convert::TryFrom<String> for Layout {
+    type Error = WindowManagerError;
+
+    fn try_from(val: String) -> Result<Self, Self::Error> {
+        Layout::try_from(val.as_str())
+    }
+}
+
 /// WinClass provides a easy way to identify the different window class types
 #[allow(dead_code)]
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum WinClass {
     CopyFromParent,
     InputOnly,
@@ -182,15 +300,15 @@ impl WinClass {
 // Implement format! support
 impl fmt::Display for WinClass {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            _ => write!(f, "{}", format!("{:?}", self).to_lowercase()),
-        }
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
     }
 }
 
 /// WinMap provides an easy way to identify the differnt window map values
 #[allow(dead_code)]
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum WinMap {
     Unmapped,
     Unviewable,
@@ -215,15 +333,15 @@ impl WinMap {
 // Implement format! support
 impl fmt::Display for WinMap {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            _ => write!(f, "{}", format!("{:?}", self).to_lowercase()),
-        }
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
     }
 }
 
 /// WinState provides an easy way to identify the different window states
 #[allow(dead_code)]
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 pub enum WinState {
     Above,
     Below,
@@ -237,6 +355,7 @@ pub enum WinState {
     Shaded,
     SkipPager,
     SkipTaskbar,
+    Sticky,
     Other(u32),
 }
 
@@ -256,9 +375,102 @@ impl WinState {
             _ if val == atoms._NET_WM_STATE_SHADED => WinState::Shaded,
             _ if val == atoms._NET_WM_STATE_SKIP_PAGER => WinState::SkipPager,
             _ if val == atoms._NET_WM_STATE_SKIP_TASKBAR => WinState::SkipTaskbar,
+            _ if val == atoms._NET_WM_STATE_STICKY => WinState::Sticky,
             _ => WinState::Other(val),
         }
     }
+
+    /// Get the atom representing the state
+    pub fn atom(&self, atoms: &AtomCollection) -> u32 {
+        match self {
+            WinState::Above => atoms._NET_WM_STATE_ABOVE,
+            WinState::Below => atoms._NET_WM_STATE_BELOW,
+            WinState::DemandsAttention => atoms._NET_WM_STATE_DEMANDS_ATTENTION,
+            WinState::Focused => atoms._NET_WM_STATE_FOCUSED,
+            WinState::Fullscreen => atoms._NET_WM_STATE_FULLSCREEN,
+            WinState::Hidden => atoms._NET_WM_STATE_HIDDEN,
+            WinState::MaxVert => atoms._NET_WM_STATE_MAXIMIZED_VERT,
+            WinState::MaxHorz => atoms._NET_WM_STATE_MAXIMIZED_HORZ,
+            WinState::Modal => atoms._NET_WM_STATE_MODAL,
+            WinState::Shaded => atoms._NET_WM_STATE_SHADED,
+            WinState::SkipPager => atoms._NET_WM_STATE_SKIP_PAGER,
+            WinState::SkipTaskbar => atoms._NET_WM_STATE_SKIP_TASKBAR,
+            WinState::Sticky => atoms._NET_WM_STATE_STICKY,
+            WinState::Other(val) => *val,
+        }
+    }
+}
+
+// Convert from &str to State
+impl convert::TryFrom<&str> for WinState {
+    type Error = WindowManagerError;
+
+    fn try_from(val: &str) -> Result<Self, Self::Error> {
+        match val.to_lowercase().as_ref() {
+            "above" => Ok(WinState::Above),
+            "below" => Ok(WinState::Below),
+            "demands-attention" => Ok(WinState::DemandsAttention),
+            "focused" => Ok(WinState::Focused),
+            "fullscreen" => Ok(WinState::Fullscreen),
+            "hidden" => Ok(WinState::Hidden),
+            "max-vert" => Ok(WinState::MaxVert),
+            "max-horz" => Ok(WinState::MaxHorz),
+            "modal" => Ok(WinState::Modal),
+            "shaded" => Ok(WinState::Shaded),
+            "skip-pager" => Ok(WinState::SkipPager),
+            "skip-taskbar" => Ok(WinState::SkipTaskbar),
+            "sticky" => Ok(WinState::Sticky),
+            _ => Err(WindowManagerError::InvalidWinStateName(val.to_string())),
+        }
+    }
+}
+
+// Convert from String to State
+impl convert::TryFrom<String> for WinState {
+    type Error = WindowManagerError;
+
+    fn try_from(val: String) -> Result<Self, Self::Error> {
+        WinState::try_from(val.as_str())
+    }
+}
+
+/// WinStateAction provides the ways a window state can be changed
+#[derive(Debug, Clone, PartialEq)]
+pub enum WinStateAction {
+    Remove,
+    Add,
+    Toggle,
+}
+
+// Implement format! support
+impl fmt::Display for WinStateAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+// Convert from &str to StateAction
+impl convert::TryFrom<&str> for WinStateAction {
+    type Error = WindowManagerError;
+
+    fn try_from(val: &str) -> Result<Self, Self::Error> {
+        match val.to_lowercase().as_ref() {
+            "remove" => Ok(WinStateAction::Remove),
+            "add" => Ok(WinStateAction::Add),
+            "toggle" => Ok(WinStateAction::Toggle),
+            _ => Err(WindowManagerError::InvalidWinStateAction(val.to_string())),
+        }
+    }
+}
+
+impl From<WinStateAction> for u32 {
+    fn from(val: WinStateAction) -> Self {
+        match val {
+            WinStateAction::Remove => 0,
+            WinStateAction::Add => 1,
+            WinStateAction::Toggle => 2,
+        }
+    }
 }
 
 // Implement format! support
@@ -274,6 +486,8 @@ impl fmt::Display for WinState {
 /// WinType provides an easy way to identify the different window types
 #[allow(dead_code)]
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum WinType {
     Combo,
     Desktop,
@@ -324,3 +538,54 @@ impl fmt::Display for WinType {
         }
     }
 }
+
+/// WinInfo provides the essential properties of a window in a single structured record
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WinInfo {
+    pub id: u32,
+    pub desktop: i32,
+    pub pid: i32,
+    pub x: i32,
+    pub y: i32,
+    pub w: u32,
+    pub h: u32,
+    pub borders: (u32, u32, u32, u32),
+    pub typ: Option<WinType>,
+    pub state: Vec<WinState>,
+    pub class: String,
+    pub name: String,
+}
+
+/// WmInfo provides the essential properties of the window manager and screen
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WmInfo {
+    pub name: String,
+    pub composite_manager: bool,
+    pub root: u32,
+    pub work_area: Rect,
+    pub width: u32,
+    pub height: u32,
+    pub desktops: u32,
+    pub monitors: Vec<Monitor>,
+}
+
+/// Monitor provides the name and location of a monitor as reported by the RandR extension
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Monitor {
+    pub name: String,
+    pub primary: bool,
+    pub rect: Rect,
+}
+
+/// DesktopInfo provides the essential properties of a desktop
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DesktopInfo {
+    pub index: u32,
+    pub name: String,
+    pub current: bool,
+    pub windows: usize,
+}
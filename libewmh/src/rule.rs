@@ -0,0 +1,148 @@
+//! `Rule` provides the placement `wmcli daemon` applies to windows when they first map. Rules are
+//! loaded from the `[[rules]]` tables of the config file and every rule that matches a window is
+//! applied in order.
+//!
+//! ```toml
+//! # Match values are case insensitive comparisons unless wrapped in slashes as a regex with an
+//! # optional trailing `i` flag. All of the given match values must match the window.
+//! [[rules]]
+//! class = "firefox"
+//! title = "/private browsing/i"
+//! shape = "halfw"
+//! position = "right"
+//! desktop = "1"
+//! states = ["above", "sticky", "skip-taskbar"]
+//! opacity = 0.9
+//!
+//! # Geometry takes the place of the shape and position
+//! [[rules]]
+//! role = "pop-up"
+//! geometry = "50%x50%+25%+25%"
+//! ```
+use std::convert::TryFrom;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{
+    selector, window, window::WinOpt, GeometrySpec, WinFilter, WinMatch, WinPosition, WinSelector, WinShape,
+    WinState, WinStateAction, WindowManager, WindowManagerError, WindowManagerResult,
+};
+
+/// Rule provides the window properties to match and the changes to make to matching windows
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct Rule {
+    pub class: Option<String>,    // window class to match
+    pub instance: Option<String>, // window instance to match
+    pub title: Option<String>,    // window title to match
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub typ: Option<String>,      // window type to match
+    pub role: Option<String>,     // window role to match
+    pub shape: Option<String>,    // shape to give the window
+    pub position: Option<String>, // position to move the window to
+    pub geometry: Option<String>, // geometry to give the window instead of a shape and position
+    pub desktop: Option<String>,  // desktop to move the window to
+    pub states: Vec<String>,      // states to add to the window
+    pub opacity: Option<f32>,     // opacity to give the window
+}
+
+impl Rule {
+    /// Build the selector matching the windows the rule applies to. A rule must match on at least
+    /// one of class, instance, title, type or role.
+    ///
+    /// ### Examples
+    /// ```
+    /// use libewmh::prelude::*;
+    /// let rule = Rule { class: Some("firefox".to_string()), ..Default::default() };
+    /// assert_eq!(rule.selector().unwrap().to_string(), "class:firefox");
+    /// ```
+    pub fn selector(&self) -> WindowManagerResult<WinSelector> {
+        let mut filters = vec![];
+        if let Some(class) = &self.class {
+            filters.push(WinFilter::Class(WinMatch::try_from(class.as_str())?));
+        }
+        if let Some(instance) = &self.instance {
+            filters.push(WinFilter::Instance(WinMatch::try_from(instance.as_str())?));
+        }
+        if let Some(title) = &self.title {
+            filters.push(WinFilter::Name(WinMatch::try_from(title.as_str())?));
+        }
+        if let Some(typ) = &self.typ {
+            let invalid = || WindowManagerError::InvalidWinSelector(typ.clone());
+            filters.push(WinFilter::Type(selector::parse_type(typ).ok_or_else(invalid)?));
+        }
+        if let Some(role) = &self.role {
+            filters.push(WinFilter::Role(WinMatch::try_from(role.as_str())?));
+        }
+        if filters.is_empty() {
+            return Err(WindowManagerError::InvalidConfig(
+                "rule must match on at least one of class, instance, title, type or role".to_string(),
+            )
+            .into());
+        }
+        Ok(WinSelector::from(filters))
+    }
+
+    /// Check that the rule's values are all valid so that mistakes are reported up front rather
+    /// than when a window maps
+    pub fn check(&self) -> WindowManagerResult<()> {
+        self.selector()?;
+        self.opt(None)?;
+        for state in &self.states {
+            WinState::try_from(state.as_str())?;
+        }
+        if let Some(opacity) = self.opacity.filter(|x| !(0.0..=1.0).contains(x)) {
+            let err = format!("opacity must be from 0.0 to 1.0: {}", opacity);
+            return Err(WindowManagerError::InvalidConfig(err).into());
+        }
+        Ok(())
+    }
+
+    /// Apply the rule's desktop, states, placement and opacity to the given window
+    ///
+    /// ### Arguments
+    /// * `wmcli` - window manager connection to manipulate the window with
+    /// * `win` - id of the window to manipulate
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = WindowManager::connect().unwrap();
+    /// let rule = Rule { class: Some("firefox".into()), shape: Some("large".into()), ..Default::default() };
+    /// rule.apply(&wmcli, 12345).unwrap();
+    /// ```
+    pub fn apply(&self, wmcli: &WindowManager, win: u32) -> WindowManagerResult<()> {
+        debug!("apply: id: {}, rule: {:?}", win, self);
+        if let Some(desktop) = &self.desktop {
+            window::to_desktop(Some(win), desktop, false)?;
+        }
+        for state in &self.states {
+            wmcli.set_win_state(win, WinStateAction::Add, WinState::try_from(state.as_str())?, None)?;
+        }
+        if let Some(opt) = self.opt(Some(win))? {
+            opt.place()?;
+        }
+        if let Some(opacity) = self.opacity {
+            wmcli.set_win_opacity(win, opacity)?;
+        }
+        Ok(())
+    }
+
+    // Build the placement options for the window if the rule places it
+    fn opt(&self, win: Option<u32>) -> WindowManagerResult<Option<WinOpt>> {
+        if let Some(geometry) = &self.geometry {
+            return Ok(Some(WinOpt::new(win).geometry(GeometrySpec::try_from(geometry.as_str())?)));
+        }
+        let mut opt = WinOpt::new(win);
+        if let Some(shape) = &self.shape {
+            opt = opt.shape(WinShape::try_from(shape.as_str())?);
+        }
+        if let Some(position) = &self.position {
+            opt = opt.pos(WinPosition::try_from(position.as_str())?);
+        }
+        Ok(Some(opt).filter(|_| self.shape.is_some() || self.position.is_some()))
+    }
+}
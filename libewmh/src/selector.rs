@@ -0,0 +1,341 @@
+//! `WinSelector` provides a small language for targeting windows by their properties rather than
+//! by raw window id. A selector is a comma separated list of filters all of which must match.
+//!
+//! * `12345` or `0x3a00007` - a raw window id
+//! * `id:12345` - window id
+//! * `class:firefox` - case insensitive window class comparison
+//! * `class~/^fire/` - window class regex, a trailing `i` makes it case insensitive e.g. `/^fire/i`
+//! * `instance:navigator` or `instance~/^nav/` - window instance comparison or regex
+//! * `name:htop` or `name~/^vim/` - window name comparison or regex
+//! * `role:browser` or `role~/^pop/` - window role comparison or regex
+//! * `pid:1234` - window process id
+//! * `desktop:2` - window desktop index
+//! * `type:normal` - window type e.g. normal, dialog, dock, utility
+//!
+//! ### Examples
+//! ```
+//! use libewmh::prelude::*;
+//! let selector = WinSelector::try_from("desktop:2,type:normal").unwrap();
+//! ```
+use std::{convert, fmt};
+
+use regex::{Regex, RegexBuilder};
+
+use crate::{WinType, WindowManager, WindowManagerError, WindowManagerResult};
+
+/// WinMatch provides the string comparison to use for a string based filter
+#[derive(Debug, Clone)]
+pub enum WinMatch {
+    Exact(String),
+    Regex(Regex),
+}
+
+impl WinMatch {
+    /// Check if the given value matches
+    pub fn is_match(&self, val: &str) -> bool {
+        match self {
+            WinMatch::Exact(x) => x.eq_ignore_ascii_case(val),
+            WinMatch::Regex(x) => x.is_match(val),
+        }
+    }
+}
+
+// Implement format! support
+impl fmt::Display for WinMatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WinMatch::Exact(x) => write!(f, ":{}", x),
+            WinMatch::Regex(x) => write!(f, "~/{}/", x),
+        }
+    }
+}
+
+// Convert from &str to WinMatch treating values wrapped in slashes as a regex
+impl convert::TryFrom<&str> for WinMatch {
+    type Error = WindowManagerError;
+
+    fn try_from(val: &str) -> Result<Self, Self::Error> {
+        if val.len() > 1 && val.starts_with('/') && (val.ends_with('/') || val.ends_with("/i")) {
+            let re = parse_regex(val).ok_or_else(|| WindowManagerError::InvalidWinSelector(val.to_string()))?;
+            return Ok(WinMatch::Regex(re));
+        }
+        Ok(WinMatch::Exact(val.to_string()))
+    }
+}
+
+/// WinFilter is a single property comparison of a selector
+#[derive(Debug, Clone)]
+pub enum WinFilter {
+    Id(u32),
+    Class(WinMatch),
+    Instance(WinMatch),
+    Name(WinMatch),
+    Role(WinMatch),
+    Pid(i32),
+    Desktop(i32),
+    Type(WinType),
+}
+
+impl WinFilter {
+    /// Check if the given window matches the filter
+    pub fn is_match(&self, wmcli: &WindowManager, win: u32) -> bool {
+        match self {
+            WinFilter::Id(id) => *id == win,
+            WinFilter::Class(x) => wmcli.win_class(win).map(|class| x.is_match(&class)).unwrap_or(false),
+            WinFilter::Instance(x) => wmcli.win_instance(win).map(|inst| x.is_match(&inst)).unwrap_or(false),
+            WinFilter::Name(x) => wmcli.win_name(win).map(|name| x.is_match(&name)).unwrap_or(false),
+            WinFilter::Role(x) => wmcli.win_role(win).map(|role| x.is_match(&role)).unwrap_or(false),
+            WinFilter::Pid(pid) => wmcli.win_pid(win).map(|x| x == *pid).unwrap_or(false),
+            WinFilter::Desktop(desktop) => wmcli.win_desktop(win).map(|x| x == *desktop).unwrap_or(false),
+            WinFilter::Type(typ) => wmcli.win_type(win).map(|x| x == *typ).unwrap_or(false),
+        }
+    }
+}
+
+// Implement format! support
+impl fmt::Display for WinFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WinFilter::Id(x) => write!(f, "id:{}", x),
+            WinFilter::Class(x) => write!(f, "class{}", x),
+            WinFilter::Instance(x) => write!(f, "instance{}", x),
+            WinFilter::Name(x) => write!(f, "name{}", x),
+            WinFilter::Role(x) => write!(f, "role{}", x),
+            WinFilter::Pid(x) => write!(f, "pid:{}", x),
+            WinFilter::Desktop(x) => write!(f, "desktop:{}", x),
+            WinFilter::Type(x) => write!(f, "type:{}", x),
+        }
+    }
+}
+
+/// WinSelector targets windows by matching all of its filters against each managed window
+#[derive(Debug, Clone)]
+pub struct WinSelector {
+    raw: String,
+    filters: Vec<WinFilter>,
+}
+
+impl WinSelector {
+    /// Get the filters making up the selector
+    pub fn filters(&self) -> &[WinFilter] {
+        &self.filters
+    }
+
+    /// Check if the given window matches the selector
+    ///
+    /// ### Arguments
+    /// * `wmcli` - window manager connection to query window properties with
+    /// * `win` - id of the window to check
+    pub fn is_match(&self, wmcli: &WindowManager, win: u32) -> bool {
+        self.filters.iter().all(|x| x.is_match(wmcli, win))
+    }
+
+    /// Resolve the selector into all of the managed windows that match
+    ///
+    /// ### Arguments
+    /// * `wmcli` - window manager connection to query window properties with
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = WindowManager::connect().unwrap();
+    /// let wins = WinSelector::try_from("class:firefox").unwrap().resolve(&wmcli).unwrap();
+    /// ```
+    pub fn resolve(&self, wmcli: &WindowManager) -> WindowManagerResult<Vec<u32>> {
+        // A lone id doesn't require the window to be managed by the window manager
+        if let [WinFilter::Id(id)] = self.filters.as_slice() {
+            return Ok(vec![*id]);
+        }
+        Ok(wmcli.get_windows(false)?.into_iter().map(|x| x.id).filter(|x| self.is_match(wmcli, *x)).collect())
+    }
+
+    /// Resolve the selector into exactly one window failing if none or many windows match
+    ///
+    /// ### Arguments
+    /// * `wmcli` - window manager connection to query window properties with
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = WindowManager::connect().unwrap();
+    /// let win = WinSelector::try_from("pid:1234").unwrap().resolve_one(&wmcli).unwrap();
+    /// ```
+    pub fn resolve_one(&self, wmcli: &WindowManager) -> WindowManagerResult<u32> {
+        match self.resolve(wmcli)?.as_slice() {
+            [] => Err(WindowManagerError::WinNotFound(self.raw.clone()).into()),
+            [win] => Ok(*win),
+            wins => Err(WindowManagerError::WinAmbiguous(self.raw.clone(), wins.len()).into()),
+        }
+    }
+}
+
+// Implement format! support
+impl fmt::Display for WinSelector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+// Build a selector from already parsed filters
+impl From<Vec<WinFilter>> for WinSelector {
+    fn from(filters: Vec<WinFilter>) -> Self {
+        let raw = filters.iter().map(|x| x.to_string()).collect::<Vec<_>>().join(",");
+        WinSelector { raw, filters }
+    }
+}
+
+// Convert from &str to WinSelector
+impl convert::TryFrom<&str> for WinSelector {
+    type Error = WindowManagerError;
+
+    fn try_from(val: &str) -> Result<Self, Self::Error> {
+        let invalid = || WindowManagerError::InvalidWinSelector(val.to_string());
+        let mut filters = vec![];
+        for term in split_terms(val) {
+            let term = term.trim();
+
+            // Bare values are always window ids
+            if let Some(id) = parse_id(term) {
+                filters.push(WinFilter::Id(id));
+                continue;
+            }
+
+            // Split into key, operator and value
+            let i = term.find([':', '~']).ok_or_else(invalid)?;
+            let (key, op, value) = (&term[..i], &term[i..i + 1], &term[i + 1..]);
+            let matcher = || -> Result<WinMatch, WindowManagerError> {
+                match op {
+                    "~" => parse_regex(value).map(WinMatch::Regex).ok_or_else(invalid),
+                    _ => Ok(WinMatch::Exact(value.to_string())),
+                }
+            };
+            if op == "~" && !["class", "instance", "name", "role"].contains(&key) {
+                return Err(invalid());
+            }
+            filters.push(match key {
+                "id" => WinFilter::Id(parse_id(value).ok_or_else(invalid)?),
+                "class" => WinFilter::Class(matcher()?),
+                "instance" => WinFilter::Instance(matcher()?),
+                "name" => WinFilter::Name(matcher()?),
+                "role" => WinFilter::Role(matcher()?),
+                "pid" => WinFilter::Pid(value.parse().map_err(|_| invalid())?),
+                "desktop" => WinFilter::Desktop(value.parse().map_err(|_| invalid())?),
+                "type" => WinFilter::Type(parse_type(value).ok_or_else(invalid)?),
+                _ => return Err(invalid()),
+            });
+        }
+        if filters.is_empty() {
+            return Err(invalid());
+        }
+        Ok(WinSelector { raw: val.to_string(), filters })
+    }
+}
+
+// Convert from String to WinSelector
+impl convert::TryFrom<String> for WinSelector {
+    type Error = WindowManagerError;
+
+    fn try_from(val: String) -> Result<Self, Self::Error> {
+        WinSelector::try_from(val.as_str())
+    }
+}
+
+// Split the selector on commas that aren't part of a /regex/
+fn split_terms(val: &str) -> Vec<&str> {
+    let mut terms = vec![];
+    let (mut start, mut in_regex, mut escaped) = (0, false, false);
+    let mut prev = ' ';
+    for (i, c) in val.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_regex => escaped = true,
+            '/' if in_regex => in_regex = false,
+            '/' if prev == '~' => in_regex = true,
+            ',' if !in_regex => {
+                terms.push(&val[start..i]);
+                start = i + 1;
+            },
+            _ => (),
+        }
+        prev = c;
+    }
+    terms.push(&val[start..]);
+    terms.into_iter().filter(|x| !x.trim().is_empty()).collect()
+}
+
+// Parse a decimal or 0x prefixed hex window id
+fn parse_id(val: &str) -> Option<u32> {
+    match val.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => val.parse().ok(),
+    }
+}
+
+// Parse either a bare regex or one wrapped in slashes with an optional trailing `i` flag
+fn parse_regex(val: &str) -> Option<Regex> {
+    let (pattern, insensitive) = match val.strip_prefix('/') {
+        Some(rest) => match rest.strip_suffix("/i") {
+            Some(pattern) => (pattern, true),
+            None => (rest.strip_suffix('/')?, false),
+        },
+        None => (val, false),
+    };
+    RegexBuilder::new(pattern).case_insensitive(insensitive).build().ok()
+}
+
+// Parse the window type name as displayed by `WinType`
+pub(crate) fn parse_type(val: &str) -> Option<WinType> {
+    match val.to_lowercase().replace(['-', '_'], "").as_ref() {
+        "combo" => Some(WinType::Combo),
+        "desktop" => Some(WinType::Desktop),
+        "dialog" => Some(WinType::Dialog),
+        "dnd" => Some(WinType::DND),
+        "dock" => Some(WinType::Dock),
+        "dropdownmenu" => Some(WinType::DropDownMenu),
+        "menu" => Some(WinType::Menu),
+        "normal" => Some(WinType::Normal),
+        "notification" => Some(WinType::Notification),
+        "popupmenu" => Some(WinType::PopupMenu),
+        "splash" => Some(WinType::Splash),
+        "toolbar" => Some(WinType::Toolbar),
+        "tooltip" => Some(WinType::ToolTip),
+        "utility" => Some(WinType::Utility),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_selector() {
+        let selector = WinSelector::try_from("0x10").unwrap();
+        assert!(matches!(selector.filters(), [WinFilter::Id(16)]));
+
+        let selector = WinSelector::try_from("desktop:2,type:normal").unwrap();
+        assert!(matches!(selector.filters(), [WinFilter::Desktop(2), WinFilter::Type(WinType::Normal)]));
+
+        let selector = WinSelector::try_from("name~/^vim,x/i,class:Firefox").unwrap();
+        match selector.filters() {
+            [WinFilter::Name(WinMatch::Regex(re)), WinFilter::Class(class)] => {
+                assert!(re.is_match("VIM,x - main.rs"));
+                assert!(class.is_match("firefox"));
+            },
+            x => panic!("unexpected filters: {:?}", x),
+        }
+
+        let selector = WinSelector::try_from("instance:navigator,role~/^pop/").unwrap();
+        assert!(matches!(selector.filters(), [WinFilter::Instance(_), WinFilter::Role(WinMatch::Regex(_))]));
+
+        assert!(matches!(WinMatch::try_from("/^fire/i"), Ok(WinMatch::Regex(_))));
+        assert!(matches!(WinMatch::try_from("firefox"), Ok(WinMatch::Exact(_))));
+        assert!(WinMatch::try_from("/(/").is_err());
+
+        assert!(WinSelector::try_from("").is_err());
+        assert!(WinSelector::try_from("pid:abc").is_err());
+        assert!(WinSelector::try_from("pid~/1/").is_err());
+        assert!(WinSelector::try_from("color:red").is_err());
+        assert!(WinSelector::try_from("name~/(/").is_err());
+    }
+}
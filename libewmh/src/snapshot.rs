@@ -0,0 +1,290 @@
+//! `Snapshot` records the layout of every managed window so that it can be restored later e.g.
+//! after restarting the window manager or docking a laptop. Snapshots are saved by name to
+//! `~/.local/share/wmcli/layouts/<name>.toml`. Windows are matched back to the live windows by
+//! class, preferring those whose role, instance and title also match, as window ids don't survive
+//! applications restarting.
+//!
+//! ### Examples
+//! ```ignore
+//! use libewmh::prelude::*;
+//! let wmcli = WindowManager::connect().unwrap();
+//! Snapshot::capture(&wmcli).unwrap().save("work").unwrap();
+//! Snapshot::load("work").unwrap().restore(&wmcli).unwrap();
+//! ```
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{
+    window, window::WinOpt, Rect, WinState, WinStateAction, WindowManager, WindowManagerResult, ALL_DESKTOPS,
+};
+
+#[cfg(feature = "config")]
+use crate::WindowManagerError;
+
+/// Snapshot provides the recorded layout of the managed windows
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct Snapshot {
+    pub windows: Vec<SnapshotWin>,
+}
+
+/// SnapshotWin provides the recorded properties of a single window
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct SnapshotWin {
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub id: u32,              // window id which is only meaningful for live windows
+    pub class: String,        // window class
+    pub instance: String,     // window instance
+    pub role: Option<String>, // window role if the window has one
+    pub title: String,        // window title
+    pub desktop: i32,         // window desktop or -1 for all desktops
+    pub rect: Rect,           // window outer frame rectangle
+    pub state: Vec<WinState>, // window states
+}
+
+// States that are restored, others are either transient or can't be set by a pager
+const RESTORED_STATES: [WinState; 9] = [
+    WinState::Above,
+    WinState::Below,
+    WinState::Fullscreen,
+    WinState::MaxHorz,
+    WinState::MaxVert,
+    WinState::Shaded,
+    WinState::SkipPager,
+    WinState::SkipTaskbar,
+    WinState::Sticky,
+];
+
+impl Snapshot {
+    /// Record the layout of every managed window
+    ///
+    /// ### Arguments
+    /// * `wmcli` - window manager connection to query the windows with
+    pub fn capture(wmcli: &WindowManager) -> WindowManagerResult<Snapshot> {
+        let mut windows = vec![];
+        for win in wmcli.get_windows(false)? {
+            // Windows may close while being recorded
+            let info = match wmcli.win_info(win.id) {
+                Ok(info) => info,
+                Err(_) => continue,
+            };
+            windows.push(SnapshotWin {
+                id: info.id,
+                instance: wmcli.win_instance(info.id).unwrap_or_default(),
+                role: wmcli.win_role(info.id).ok(),
+                desktop: info.desktop,
+                rect: window::outer_rect(&info),
+                class: info.class,
+                title: info.name,
+                // Unknown states are atom ids which don't carry over to other X sessions
+                state: info.state.into_iter().filter(|x| !matches!(x, WinState::Other(_))).collect(),
+            });
+        }
+        Ok(Snapshot { windows })
+    }
+
+    /// Restore the recorded layout to the matching live windows returning how many windows were
+    /// restored along with why any matching windows couldn't be. Recorded windows without a live
+    /// match are skipped.
+    ///
+    /// ### Arguments
+    /// * `wmcli` - window manager connection to manipulate the windows with
+    pub fn restore(&self, wmcli: &WindowManager) -> WindowManagerResult<(usize, Vec<String>)> {
+        let live = Snapshot::capture(wmcli)?;
+        let desktops = wmcli.desktops()?;
+        let (mut restored, mut failed) = (0, vec![]);
+        for (saved, win) in pair(&self.windows, &live.windows) {
+            let (saved, win) = (&self.windows[saved], &live.windows[win]);
+            debug!("restore: id: {}, class: {}, title: {}", win.id, win.class, win.title);
+
+            // A window closing mid restore shouldn't stop the others being restored
+            match restore_win(wmcli, saved, win, desktops) {
+                Ok(()) => restored += 1,
+                Err(err) => failed.push(format!("{} '{}': {}", win.class, win.title, err)),
+            }
+        }
+        Ok((restored, failed))
+    }
+}
+
+#[cfg(feature = "config")]
+impl Snapshot {
+    /// Get the path of the named snapshot i.e. `$XDG_DATA_HOME/wmcli/layouts/<name>.toml` falling
+    /// back to `~/.local/share/wmcli/layouts/<name>.toml`
+    ///
+    /// ### Arguments
+    /// * `name` - name of the snapshot
+    pub fn path(name: &str) -> WindowManagerResult<std::path::PathBuf> {
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            return Err(WindowManagerError::InvalidSnapshot(name.to_string()).into());
+        }
+        let dir = match std::env::var_os("XDG_DATA_HOME").filter(|x| !x.is_empty()) {
+            Some(dir) => std::path::PathBuf::from(dir),
+            None => match std::env::var_os("HOME") {
+                Some(home) => std::path::PathBuf::from(home).join(".local").join("share"),
+                None => return Err(WindowManagerError::InvalidSnapshot("HOME is not set".to_string()).into()),
+            },
+        };
+        Ok(dir.join("wmcli").join("layouts").join(format!("{}.toml", name)))
+    }
+
+    /// Save the snapshot by name replacing any existing snapshot of the same name
+    ///
+    /// ### Arguments
+    /// * `name` - name to save the snapshot as
+    pub fn save(&self, name: &str) -> WindowManagerResult<()> {
+        let path = Snapshot::path(name)?;
+        let err = |err: &dyn std::fmt::Display| {
+            WindowManagerError::InvalidSnapshot(format!("{}: {}", path.display(), err))
+        };
+        let data = toml::to_string(self).map_err(|x| err(&x))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|x| err(&x))?;
+        }
+        std::fs::write(&path, data).map_err(|x| err(&x))?;
+        debug!("save: path: {}, windows: {}", path.display(), self.windows.len());
+        Ok(())
+    }
+
+    /// Load the named snapshot
+    ///
+    /// ### Arguments
+    /// * `name` - name the snapshot was saved as
+    pub fn load(name: &str) -> WindowManagerResult<Snapshot> {
+        let path = Snapshot::path(name)?;
+        let err = |err: &dyn std::fmt::Display| {
+            WindowManagerError::InvalidSnapshot(format!("{}: {}", path.display(), err))
+        };
+        let data = std::fs::read_to_string(&path).map_err(|x| err(&x))?;
+        Ok(toml::from_str(&data).map_err(|x| err(&x.message()))?)
+    }
+}
+
+// Restore the recorded desktop, geometry and states to the live window
+fn restore_win(
+    wmcli: &WindowManager, saved: &SnapshotWin, win: &SnapshotWin, desktops: u32,
+) -> WindowManagerResult<()> {
+    // Windows on all desktops are recorded as -1 while desktops that no longer exist e.g. after
+    // restarting the window manager are clamped to the last desktop
+    let desktop = match saved.desktop {
+        -1 => ALL_DESKTOPS,
+        x => (x.max(0) as u32).min(desktops.saturating_sub(1)),
+    };
+    if desktop as i32 != win.desktop {
+        wmcli.set_win_desktop(win.id, desktop)?;
+    }
+
+    // The window manager ignores moving fullscreen or shaded windows so unwanted states are removed
+    // before placing the window. Placing drops any maximized state which is then added back.
+    let maxed = |x: &WinState| matches!(x, WinState::MaxHorz | WinState::MaxVert);
+    for state in RESTORED_STATES {
+        if !saved.state.contains(&state) && win.state.contains(&state) && !maxed(&state) {
+            wmcli.set_win_state(win.id, WinStateAction::Remove, state, None)?;
+        }
+    }
+    WinOpt::new(Some(win.id)).rect(saved.rect).place()?;
+    for state in RESTORED_STATES {
+        if saved.state.contains(&state) && (!win.state.contains(&state) || maxed(&state)) {
+            wmcli.set_win_state(win.id, WinStateAction::Add, state, None)?;
+        }
+    }
+    Ok(())
+}
+
+// Pair the recorded windows with the live windows returning the indices of each pair. The best
+// scoring pairs are taken first so that a loosely matching window can't take the place of an
+// exact match.
+fn pair(saved: &[SnapshotWin], live: &[SnapshotWin]) -> Vec<(usize, usize)> {
+    let mut scores = vec![];
+    for (i, a) in saved.iter().enumerate() {
+        for (j, b) in live.iter().enumerate() {
+            if let Some(score) = score(a, b) {
+                scores.push((score, i, j));
+            }
+        }
+    }
+
+    // Highest score first keeping the recorded and live order for ties
+    scores.sort_by_key(|(score, i, j)| (std::cmp::Reverse(*score), *i, *j));
+    let (mut used_saved, mut used_live) = (vec![false; saved.len()], vec![false; live.len()]);
+    let mut pairs = vec![];
+    for (_, i, j) in scores {
+        if !used_saved[i] && !used_live[j] {
+            used_saved[i] = true;
+            used_live[j] = true;
+            pairs.push((i, j));
+        }
+    }
+    pairs.sort();
+    pairs
+}
+
+// Score how well the recorded window matches the live window. The class must match while a
+// matching role counts for more than the instance or the title which often changes.
+fn score(saved: &SnapshotWin, live: &SnapshotWin) -> Option<u32> {
+    if !saved.class.eq_ignore_ascii_case(&live.class) {
+        return None;
+    }
+    let mut score = 0;
+    if saved.role.is_some() && saved.role == live.role {
+        score += 4;
+    }
+    if saved.instance == live.instance {
+        score += 2;
+    }
+    if saved.title == live.title {
+        score += 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn win(class: &str, role: Option<&str>, title: &str) -> SnapshotWin {
+        SnapshotWin {
+            class: class.to_string(),
+            instance: class.to_lowercase(),
+            role: role.map(String::from),
+            title: title.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_pair() {
+        let saved = [
+            win("Firefox", Some("browser"), "Mail"),
+            win("Firefox", Some("browser"), "News"),
+            win("XTerm", None, "htop"),
+            win("Gimp", None, "image"),
+        ];
+        let live = [
+            win("XTerm", None, "bash"),
+            win("Firefox", Some("browser"), "News"),
+            win("Firefox", Some("browser"), "Weather"),
+        ];
+
+        // The exact match is taken first leaving the other browser window for the first record
+        assert_eq!(pair(&saved, &live), vec![(0, 2), (1, 1), (2, 0)]);
+        assert_eq!(pair(&saved, &[]), vec![]);
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_snapshot_toml() {
+        let mut saved = win("Firefox", Some("browser"), "News");
+        saved.rect = Rect::new(-10, 20, 960, 1080);
+        saved.state = vec![WinState::MaxVert, WinState::Above, WinState::SkipTaskbar];
+        let snapshot = Snapshot { windows: vec![saved, win("XTerm", None, "htop")] };
+        let data = toml::to_string(&snapshot).unwrap();
+        assert!(data.contains(r#"state = ["max-vert", "above", "skip-taskbar"]"#), "{}", data);
+        assert_eq!(toml::from_str::<Snapshot>(&data).unwrap(), snapshot);
+        assert!(Snapshot::path("../work").is_err());
+    }
+}
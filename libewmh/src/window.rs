@@ -1,4 +1,10 @@
-use crate::{WinGravity, WinPosition, WinShape, WindowManager, WindowManagerResult};
+use tracing::debug;
+
+use crate::{
+    desktop, geometry, Direction, GeometrySpec, Grid, Insets, Rect, WinInfo, WinPosition, WinSelector, WinShape,
+    WinState, WinStateAction, WinType, WindowManager, WindowManagerError, WindowManagerResult, WmInfo,
+    ALL_DESKTOPS,
+};
 
 pub struct Window {
     pub id: u32,
@@ -8,10 +14,14 @@ pub struct WinOpt {
     win: Option<u32>,
     w: Option<u32>,
     h: Option<u32>,
-    x: Option<u32>,
-    y: Option<u32>,
+    x: Option<i32>,
+    y: Option<i32>,
     shape: Option<WinShape>,
     pos: Option<WinPosition>,
+    geometry: Option<GeometrySpec>,
+    grid: Option<Grid>,
+    rect: Option<Rect>,
+    monitor: Option<String>,
 }
 
 impl WinOpt {
@@ -34,6 +44,10 @@ impl WinOpt {
             y: Default::default(),
             shape: Default::default(),
             pos: Default::default(),
+            geometry: Default::default(),
+            grid: Default::default(),
+            rect: Default::default(),
+            monitor: Default::default(),
         }
     }
 
@@ -57,7 +71,8 @@ impl WinOpt {
     }
 
     /// Set the x, y location the window should be. This option takes priority over
-    /// and will set the position option to None.
+    /// and will set the position option to None. Negative values are valid for monitors to the
+    /// left of or above the origin and for windows partly off screen.
     ///
     /// ### Arguments
     /// * `x` - x coordinate the window moved to
@@ -66,9 +81,9 @@ impl WinOpt {
     /// ### Examples
     /// ```
     /// use libewmh::prelude::*;
-    /// let win = WinOpt::new(None).location(0, 0);
+    /// let win = WinOpt::new(None).location(-1920, 0);
     /// ```
-    pub fn location(mut self, x: u32, y: u32) -> Self {
+    pub fn location(mut self, x: i32, y: i32) -> Self {
         self.x = Some(x);
         self.y = Some(y);
         self.pos = None;
@@ -111,6 +126,94 @@ impl WinOpt {
         self
     }
 
+    /// Set the geometry the window should have. This option takes priority over and will set the
+    /// size, location, shape and position options to None.
+    ///
+    /// ### Arguments
+    /// * `geometry` - X style geometry with optional percentages of the work area
+    ///
+    /// ### Examples
+    /// ```
+    /// use libewmh::prelude::*;
+    /// let win = WinOpt::new(None).geometry(GeometrySpec::try_from("50%x100%+50%+0").unwrap());
+    /// ```
+    pub fn geometry(mut self, geometry: GeometrySpec) -> Self {
+        self.w = None;
+        self.h = None;
+        self.x = None;
+        self.y = None;
+        self.shape = None;
+        self.pos = None;
+        self.grid = None;
+        self.rect = None;
+        self.geometry = Some(geometry);
+        self
+    }
+
+    /// Set the grid cells the window should cover. This option takes priority over and will set
+    /// the size, location, shape, position and geometry options to None.
+    ///
+    /// ### Arguments
+    /// * `grid` - grid dividing the work area and the cells to span
+    ///
+    /// ### Examples
+    /// ```
+    /// use libewmh::prelude::*;
+    /// let win = WinOpt::new(None).grid(Grid::new(3, 2).cell(0, 0).span(2, 1));
+    /// ```
+    pub fn grid(mut self, grid: Grid) -> Self {
+        self.w = None;
+        self.h = None;
+        self.x = None;
+        self.y = None;
+        self.shape = None;
+        self.pos = None;
+        self.geometry = None;
+        self.grid = Some(grid);
+        self.rect = None;
+        self
+    }
+
+    /// Set the exact outer frame rectangle the window should cover. This option takes priority
+    /// over and will set all other shape and placement options to None.
+    ///
+    /// ### Arguments
+    /// * `rect` - outer frame rectangle in root window coordinates
+    ///
+    /// ### Examples
+    /// ```
+    /// use libewmh::prelude::*;
+    /// let win = WinOpt::new(None).rect(Rect::new(0, 0, 960, 1080));
+    /// ```
+    pub fn rect(mut self, rect: Rect) -> Self {
+        self.w = None;
+        self.h = None;
+        self.x = None;
+        self.y = None;
+        self.shape = None;
+        self.pos = None;
+        self.geometry = None;
+        self.grid = None;
+        self.rect = Some(rect);
+        self
+    }
+
+    /// Set the monitor the shape and position should be relative to. By default the monitor
+    /// containing the center of the window is used.
+    ///
+    /// ### Arguments
+    /// * `monitor` - name or index of the monitor or `primary` for the primary monitor
+    ///
+    /// ### Examples
+    /// ```
+    /// use libewmh::prelude::*;
+    /// let win = WinOpt::new(None).monitor("primary").pos(WinPosition::Center);
+    /// ```
+    pub fn monitor(mut self, monitor: &str) -> Self {
+        self.monitor = Some(monitor.to_string());
+        self
+    }
+
     // Check if any options are set
     fn any(&self) -> bool {
         self.w.is_some()
@@ -119,6 +222,9 @@ impl WinOpt {
             || self.y.is_some()
             || self.shape.is_some()
             || self.pos.is_some()
+            || self.geometry.is_some()
+            || self.grid.is_some()
+            || self.rect.is_some()
     }
 
     /// Place the window according to the specified options
@@ -133,252 +239,505 @@ impl WinOpt {
         let wmcli = WindowManager::connect()?;
 
         // Get window properties
-        let win = self.win.unwrap_or(wmcli.active_win()?);
-        let (bl, br, bt, bb) = wmcli.win_borders(win)?;
-        let (_, _, w, h) = wmcli.win_geometry(win)?;
-
-        // Shape the window as directed
-        let (gravity, sw, sh) = if let Some(shape) = self.shape {
-            let (gravity, sw, sh) = shape_win(&wmcli, win, w, h, bl + br, bt + bb, shape)?;
-
-            // Don't use gravity if positioning is required
-            if self.pos.is_some() || self.x.is_some() || self.y.is_some() {
-                (None, sw, sh)
-            } else {
-                (gravity, sw, sh)
-            }
-        } else if self.w.is_some() && self.h.is_some() {
-            (None, Some(self.w.unwrap()), Some(self.h.unwrap()))
-        } else {
-            (None, None, None)
+        let win = match self.win {
+            Some(win) => win,
+            None => wmcli.active_win()?,
         };
+        let frame = Insets::from(wmcli.win_borders(win)?);
+        let (x, y, w, h) = wmcli.win_geometry(win)?;
+        let mut current = Rect::new(x, y, w as i32, h as i32).outer(&frame);
+        let area = work_area(&wmcli, self.monitor.as_deref(), &current)?;
 
-        // Position the window if directed
-        let (x, y) = if let Some(pos) = self.pos {
-            move_win(&wmcli, win, sw.unwrap_or(w), sh.unwrap_or(h), bl + br, bt + bb, pos)?
-        } else if self.x.is_some() && self.y.is_some() {
-            (self.x, self.y)
-        } else {
-            (None, None)
+        // Maximizing is left to the window manager and all other shapes and positions require
+        // the window to be unmaximized first. Only maximized windows are unmaximized as some
+        // window managers rewrite the state regardless, notifying anyone watching it.
+        match self.shape {
+            Some(WinShape::Max) => return wmcli.maximize_win(win),
+            Some(WinShape::UnMax) if self.pos.is_none() => return wmcli.unmaximize_win(win),
+            _ => {
+                let placing = self.shape.is_some()
+                    || self.pos.is_some()
+                    || self.geometry.is_some()
+                    || self.grid.is_some()
+                    || self.rect.is_some();
+                let maxed = || {
+                    let state = wmcli.win_state(win).unwrap_or_default();
+                    state.iter().any(|x| matches!(x, WinState::MaxHorz | WinState::MaxVert))
+                };
+                if placing && maxed() {
+                    // Anything kept from the current geometry should be the unmaximized geometry
+                    wmcli.unmaximize_win(win)?;
+                    let (x, y, w, h) = wmcli.win_geometry(win)?;
+                    current = Rect::new(x, y, w as i32, h as i32).outer(&frame);
+                }
+            },
+        }
+
+        // Explicit sizes keep the window's top left corner where it is
+        let start = match (self.w, self.h) {
+            (Some(w), Some(h)) => {
+                Rect::new(current.x, current.y, w as i32 + frame.horz(), h as i32 + frame.vert())
+            },
+            _ => current,
         };
+        let mut target = match (self.rect, self.grid, self.geometry) {
+            (Some(rect), ..) => rect,
+            (_, Some(grid), _) => grid.rect(&area)?,
+            (.., Some(spec)) => spec.resolve(&area, &frame, &current),
+            _ => geometry::target(&area, &frame, &start, self.shape, self.pos),
+        };
+        if let (Some(x), Some(y)) = (self.x, self.y) {
+            target.x = x;
+            target.y = y;
+        }
+
+        // Send the full geometry as the window manager may restore parts of the geometry it had
+        // before being maximized that aren't sent
+        let inner = target.inner(&frame);
+        let (w, h) = (inner.w.max(1) as u32, inner.h.max(1) as u32);
 
         // Execute if reason to
         if execute {
-            wmcli.move_resize_win(win, gravity, x, y, sw, sh)
+            wmcli.move_resize_win(win, None, Some(target.x), Some(target.y), Some(w), Some(h))
         } else {
             Ok(())
         }
     }
 }
 
-/// List out a x11 information including the window manager's name, if there is a composite
-/// manager running and what the screen and work screen sizes are.
+/// Close the window gracefully or when forced kill the window's client
 ///
 /// ### Arguments
-/// * `win` - id of the window to manipulate else the active window will be used
+/// * `win` - id of the window to close else the active window will be used
+/// * `force` - kill the client owning the window rather than asking it to close
 ///
 /// ### Examples
 /// ```ignore
 /// use libewmh::prelude::*;
-/// libewmh::info(None).unwrap();
+/// libewmh::window::close(None, false).unwrap();
 /// ```
-pub fn info(win: Option<u32>) -> WindowManagerResult<()> {
+pub fn close(win: Option<u32>, force: bool) -> WindowManagerResult<()> {
     let wmcli = WindowManager::connect()?;
-    let (_, wm_name) = wmcli.winmgr()?;
-    let win = win.unwrap_or(wmcli.active_win()?);
-    println!("X11 Information");
-    println!("-----------------------------------------------------------------------");
-    println!("Window Manager:    {}", wm_name);
-    println!("Composite Manager: {}", wmcli.composite_manager()?);
-    println!("Root Window:       {}", wmcli.root);
-    println!("Work area:         {}x{}", wmcli.work_width, wmcli.work_height);
-    println!("Screen Size:       {}x{}", wmcli.width, wmcli.height);
-    println!("Desktops:          {}", wmcli.desktops()?);
-    println!();
-    println!("Active Window");
-    println!("{:-<120}", "");
-    print_win_header();
-    print_win_details(&wmcli, win)?;
-    wmcli.win_attributes(win)?;
-    Ok(())
+    let win = match win {
+        Some(win) => win,
+        None => wmcli.active_win()?,
+    };
+    if force {
+        wmcli.kill_win(win)
+    } else {
+        wmcli.close_win(win)
+    }
 }
 
-/// List out the windows the window manager is managing and their essential properties
+/// Activate the window switching to its desktop if needed
 ///
 /// ### Arguments
-/// * `all` - when set to true will list all x11 windows not just those the window manager lists
+/// * `win` - id of the window to activate else the active window will be used
 ///
 /// ### Examples
 /// ```ignore
 /// use libewmh::prelude::*;
-/// libewmh::list().unwrap();
+/// libewmh::window::focus(Some(12345)).unwrap();
 /// ```
-pub fn list(all: bool) -> WindowManagerResult<()> {
+pub fn focus(win: Option<u32>) -> WindowManagerResult<()> {
     let wmcli = WindowManager::connect()?;
-    print_win_header();
-    for win in wmcli.get_windows(all)? {
-        print_win_details(&wmcli, win.id)?;
+    let win = match win {
+        Some(win) => win,
+        None => wmcli.active_win()?,
+    };
+    wmcli.activate_win(win)
+}
+
+/// Activate the nearest visible window on the current desktop in the given direction from the
+/// window. Nothing happens when there is no window in that direction.
+///
+/// ### Arguments
+/// * `win` - id of the window to look from else the active window will be used
+/// * `dir` - direction to look for the window to activate in
+///
+/// ### Examples
+/// ```ignore
+/// use libewmh::prelude::*;
+/// libewmh::window::focus_dir(None, Direction::Left).unwrap();
+/// ```
+pub fn focus_dir(win: Option<u32>, dir: Direction) -> WindowManagerResult<()> {
+    match neighbour(win, dir)? {
+        Some(other) => WindowManager::connect()?.activate_win(other),
+        None => Ok(()),
+    }
+}
+
+/// Cycle through the windows in most recently used order according to the stacking order. Going
+/// forward activates the least recently used window such that repeating it visits every window
+/// in turn while going in reverse returns to the most recently used window before this one.
+///
+/// ### Arguments
+/// * `win` - id of the window to cycle from else the active window will be used
+/// * `reverse` - activate the most rather than the least recently used window
+/// * `desktop` - index or name of the desktop to cycle through, `all` for all desktops, else the
+///   current desktop
+/// * `selector` - only cycle through the windows matching the given selector
+///
+/// ### Examples
+/// ```ignore
+/// use libewmh::prelude::*;
+/// let terminals = WinSelector::try_from("class:xterm").unwrap();
+/// libewmh::window::focus_cycle(None, false, None, Some(&terminals)).unwrap();
+/// ```
+pub fn focus_cycle(
+    win: Option<u32>, reverse: bool, desktop: Option<&str>, selector: Option<&WinSelector>,
+) -> WindowManagerResult<()> {
+    let wmcli = WindowManager::connect()?;
+    let win = match win {
+        Some(win) => win,
+        None => wmcli.active_win()?,
+    };
+    let desktop = match desktop {
+        Some("all") => None,
+        Some(desktop) => Some(desktop::resolve(&wmcli, desktop)? as i32),
+        None => Some(wmcli.current_desktop()? as i32),
+    };
+
+    // Most recently used first skipping the window being cycled from
+    let mut wins = vec![];
+    for id in wmcli.stacking_order()?.into_iter().rev().filter(|x| *x != win) {
+        let info = match wmcli.win_info(id) {
+            Ok(info) => info,
+            _ => continue,
+        };
+        if desktop.map(|x| info.desktop == x || info.desktop as u32 == ALL_DESKTOPS).unwrap_or(true)
+            && !matches!(info.typ, Some(WinType::Desktop) | Some(WinType::Dock))
+            && selector.map(|x| x.is_match(&wmcli, id)).unwrap_or(true)
+        {
+            wins.push(id);
+        }
+    }
+    let other = if reverse { wins.first() } else { wins.last() };
+    debug!("focus_cycle: id: {}, reverse: {}, other: {:?}", win, reverse, other);
+    match other {
+        Some(other) => wmcli.activate_win(*other),
+        None => Ok(()),
+    }
+}
+
+/// Find the nearest visible window on the current desktop in the given direction from the window
+/// returning None when there is no window in that direction.
+///
+/// ### Arguments
+/// * `win` - id of the window to look from else the active window will be used
+/// * `dir` - direction to look for a window in
+///
+/// ### Examples
+/// ```ignore
+/// use libewmh::prelude::*;
+/// let left = libewmh::window::neighbour(None, Direction::Left).unwrap();
+/// ```
+pub fn neighbour(win: Option<u32>, dir: Direction) -> WindowManagerResult<Option<u32>> {
+    let wmcli = WindowManager::connect()?;
+    let win = match win {
+        Some(win) => win,
+        None => wmcli.active_win()?,
+    };
+    let from = outer_rect(&wmcli.win_info(win)?);
+
+    // Candidates are the other visible windows on the current desktop or on all desktops
+    let desktop = wmcli.current_desktop()? as i32;
+    let mut wins = vec![];
+    let mut rects = vec![];
+    for x in wmcli.get_windows(false)? {
+        let info = match wmcli.win_info(x.id) {
+            Ok(info) if info.id != win => info,
+            _ => continue,
+        };
+        if (info.desktop == desktop || info.desktop as u32 == ALL_DESKTOPS)
+            && !info.state.contains(&WinState::Hidden)
+            && !matches!(info.typ, Some(WinType::Desktop) | Some(WinType::Dock))
+        {
+            wins.push(info.id);
+            rects.push(outer_rect(&info));
+        }
+    }
+    let other = geometry::nearest(&from, &rects, dir).map(|i| wins[i]);
+    debug!("neighbour: id: {}, dir: {}, neighbour: {:?}", win, dir, other);
+    Ok(other)
+}
+
+/// Swap the outer frame rectangles of the two windows moving them together. Maximized states
+/// travel with the geometry i.e. the window taking a maximized window's place is maximized.
+///
+/// ### Arguments
+/// * `win` - id of the window to swap else the active window will be used
+/// * `other` - id of the window to swap with
+///
+/// ### Examples
+/// ```ignore
+/// use libewmh::prelude::*;
+/// let left = libewmh::window::neighbour(None, Direction::Left).unwrap();
+/// libewmh::window::swap(None, left.unwrap()).unwrap();
+/// ```
+pub fn swap(win: Option<u32>, other: u32) -> WindowManagerResult<()> {
+    let wmcli = WindowManager::connect()?;
+    let win = match win {
+        Some(win) => win,
+        None => wmcli.active_win()?,
+    };
+    if win == other {
+        return Ok(());
+    }
+    let infos = [wmcli.win_info(win)?, wmcli.win_info(other)?];
+
+    // Maximized windows won't move so drop the states before swapping then swap the states too
+    let maxed = |info: &WinInfo| {
+        [WinState::MaxHorz, WinState::MaxVert].into_iter().filter(|x| info.state.contains(x)).collect::<Vec<_>>()
+    };
+    let states = [maxed(&infos[0]), maxed(&infos[1])];
+    for (info, states) in infos.iter().zip(&states) {
+        if !states.is_empty() {
+            wmcli.unmaximize_win(info.id)?;
+        }
+    }
+
+    // Each window takes the other's outer rectangle keeping its own frame extents
+    let target = |info: &WinInfo, to: &WinInfo| {
+        let outer = outer_rect(to);
+        let inner = outer.inner(&Insets::from(info.borders));
+        Rect::new(outer.x, outer.y, inner.w, inner.h)
+    };
+    wmcli.move_resize_wins(&[(win, target(&infos[0], &infos[1])), (other, target(&infos[1], &infos[0]))])?;
+    for (info, states) in infos.iter().zip(states.iter().rev()) {
+        if let [first, rest @ ..] = states.as_slice() {
+            wmcli.set_win_state(info.id, WinStateAction::Add, first.clone(), rest.first().cloned())?;
+        }
     }
     Ok(())
 }
 
-fn print_win_header() {
-    println!(
-        "{:<8} {:<3} {:<6} {:<5} {:<5} {:<4} {:<4} {:<8} {:<7} {:<18} {:<18} {}",
-        "ID", "DSK", "PID", "X", "Y", "W", "H", "BORDERS", "TYPE", "STATE", "CLASS", "NAME"
-    );
+// Get the window's outer frame rectangle from its details
+pub(crate) fn outer_rect(info: &WinInfo) -> Rect {
+    Rect::new(info.x, info.y, info.w as i32, info.h as i32).outer(&Insets::from(info.borders))
 }
 
-fn print_win_details(wm: &WindowManager, win: u32) -> WindowManagerResult<()> {
-    let pid = wm.win_pid(win).unwrap_or(-1);
-    let desktop = wm.win_desktop(win).unwrap_or(-1);
-    let typ = wm.win_type(win);
-    let states = wm.win_state(win);
-    let (x, y, w, h) = wm.win_geometry(win)?;
-    let (l, r, t, b) = wm.win_borders(win).unwrap_or((0, 0, 0, 0));
-    let class = wm.win_class(win).unwrap_or("".to_owned());
-    let name = wm.win_name(win).unwrap_or("".to_owned());
-    println!(
-        "{:<8} {:<3} {:<6} {:<5} {:<5} {:<4} {:<4} {:<8} {:<7} {:<18} {:<18} {}",
-        format!("{:0>8}", win),
-        format!("{:>2}", desktop),
-        pid,
-        format!("{:<4}", x),
-        format!("{:<4}", y),
-        format!("{:<4}", w),
-        format!("{:<4}", h),
-        format!("{},{},{},{}", l, r, t, b),
-        match typ {
-            Ok(x) => x.to_string(),
-            Err(_) => "Error".to_owned(),
-        },
-        match states {
-            Ok(x) => format!("{:?}", x),
-            _ => format!("{:?}", states),
-        },
-        class,
-        name
-    );
+/// Add, remove or toggle the given window states
+///
+/// ### Arguments
+/// * `win` - id of the window to manipulate else the active window will be used
+/// * `action` - whether to add, remove or toggle the states
+/// * `state1` - first state to change
+/// * `state2` - optional second state to change at the same time
+///
+/// ### Examples
+/// ```ignore
+/// use libewmh::prelude::*;
+/// libewmh::window::state(None, WinStateAction::Toggle, WinState::Above, None).unwrap();
+/// ```
+pub fn state(
+    win: Option<u32>, action: WinStateAction, state1: WinState, state2: Option<WinState>,
+) -> WindowManagerResult<()> {
+    let wmcli = WindowManager::connect()?;
+    let win = match win {
+        Some(win) => win,
+        None => wmcli.active_win()?,
+    };
+    wmcli.set_win_state(win, action, state1, state2)
+}
+
+/// Move the window to another desktop optionally following it there
+///
+/// ### Arguments
+/// * `win` - id of the window to move else the active window will be used
+/// * `desktop` - index or name of the desktop, `next`, `prev` or `all` for all desktops
+/// * `follow` - switch to the desktop and activate the window after moving it
+///
+/// ### Examples
+/// ```ignore
+/// use libewmh::prelude::*;
+/// libewmh::window::to_desktop(None, "next", true).unwrap();
+/// ```
+pub fn to_desktop(win: Option<u32>, desktop: &str, follow: bool) -> WindowManagerResult<()> {
+    let wmcli = WindowManager::connect()?;
+    let win = match win {
+        Some(win) => win,
+        None => wmcli.active_win()?,
+    };
+
+    // Next and previous are relative to the window's desktop wrapping around at the ends
+    let desktops = wmcli.desktops()?;
+    if desktops == 0 {
+        return Err(WindowManagerError::InvalidDesktop("no desktops to move the window to".to_owned()).into());
+    }
+    let current = match wmcli.win_desktop(win) {
+        Ok(x) if x >= 0 => x as u32,
+        _ => wmcli.current_desktop()?,
+    };
+    let desktop = match desktop {
+        "next" => (current + 1) % desktops,
+        "prev" => (current + desktops - 1) % desktops,
+        "all" => ALL_DESKTOPS,
+        _ => desktop::resolve(&wmcli, desktop)?,
+    };
+
+    // Switch to the target desktop directly rather than having activating the window find it as
+    // the window manager may not have moved the window yet
+    wmcli.set_win_desktop(win, desktop)?;
+    if follow && desktop != ALL_DESKTOPS {
+        wmcli.set_current_desktop(desktop)?;
+        wmcli.activate_win(win)?;
+    }
     Ok(())
 }
 
-/// Move the given window or active window if not given without changing its size
-fn move_win(
-    wmcli: &WindowManager, win: u32, w: u32, h: u32, bw: u32, bh: u32, pos: WinPosition,
-) -> WindowManagerResult<(Option<u32>, Option<u32>)> {
-    wmcli.unmaximize_win(win)?;
-
-    // Pre-calculations
-    let cx = if (w + bw) / 2 >= wmcli.work_width / 2 { 0 } else { wmcli.work_width / 2 - (w + bw) / 2 }; // center x
-    let cy = if (h + bh) / 2 >= wmcli.work_height / 2 { 0 } else { wmcli.work_height / 2 - (h + bh) / 2 }; // center y
-    let lx = if w + bw >= wmcli.work_width { 0 } else { wmcli.work_width - w - bw }; // left x
-    let ty = if h + bh >= wmcli.work_height { 0 } else { wmcli.work_height - h - bh }; // top y
-
-    // Interpret the position as x, y cordinates
-    Ok(match pos {
-        WinPosition::Center => (Some(cx), Some(cy)),
-        WinPosition::Left => (Some(0), None),
-        WinPosition::Right => (Some(lx), None),
-        WinPosition::Top => (None, Some(0)),
-        WinPosition::Bottom => (None, Some(ty)),
-        WinPosition::TopLeft => (Some(0), Some(0)),
-        WinPosition::TopRight => (Some(lx), Some(0)),
-        WinPosition::BottomLeft => (Some(0), Some(ty)),
-        WinPosition::BottomRight => (Some(lx), Some(ty)),
-        WinPosition::LeftCenter => (Some(0), Some(cy)),
-        WinPosition::RightCenter => (Some(lx), Some(cy)),
-        WinPosition::TopCenter => (Some(cx), Some(0)),
-        WinPosition::BottomCenter => (Some(cx), Some(ty)),
-    })
+/// Move the window to another monitor keeping its position and size relative to the monitor's
+/// work area. Maximized and fullscreen windows stay that way on the new monitor.
+///
+/// ### Arguments
+/// * `win` - id of the window to move else the active window will be used
+/// * `monitor` - name or index of the monitor, `primary`, `next` or `prev`
+///
+/// ### Examples
+/// ```ignore
+/// use libewmh::prelude::*;
+/// libewmh::window::to_monitor(None, "next").unwrap();
+/// ```
+pub fn to_monitor(win: Option<u32>, monitor: &str) -> WindowManagerResult<()> {
+    let wmcli = WindowManager::connect()?;
+    let win = match win {
+        Some(win) => win,
+        None => wmcli.active_win()?,
+    };
+
+    // Determine the source monitor as the one containing the window's center
+    let frame = Insets::from(wmcli.win_borders(win).unwrap_or((0, 0, 0, 0)));
+    let (x, y, w, h) = wmcli.win_geometry(win)?;
+    let outer = Rect::new(x, y, w as i32, h as i32).outer(&frame);
+    let monitors = wmcli.monitors()?;
+    let i = monitors
+        .iter()
+        .position(|x| x.rect.contains(outer.center()))
+        .or_else(|| monitors.iter().position(|x| x.primary))
+        .ok_or_else(|| WindowManagerError::InvalidMonitor(monitor.to_string()))?;
+    let src = &monitors[i];
+    let dst = match monitor {
+        "next" => monitors[(i + 1) % monitors.len()].clone(),
+        "prev" => monitors[(i + monitors.len() - 1) % monitors.len()].clone(),
+        _ => wmcli.monitor(monitor)?,
+    };
+    if dst == *src {
+        return Ok(());
+    }
+
+    // Maximized and fullscreen windows won't move so drop the state then restore it after. The
+    // geometry is read again so that the window keeps its unmaximized size on the new monitor.
+    let states = wmcli.win_state(win).unwrap_or_default();
+    let maximized = states.contains(&WinState::MaxHorz) || states.contains(&WinState::MaxVert);
+    let fullscreen = states.contains(&WinState::Fullscreen);
+    if maximized {
+        wmcli.unmaximize_win(win)?;
+    }
+    if fullscreen {
+        wmcli.set_win_state(win, WinStateAction::Remove, WinState::Fullscreen, None)?;
+    }
+    let outer = if maximized || fullscreen {
+        let (x, y, w, h) = wmcli.win_geometry(win)?;
+        Rect::new(x, y, w as i32, h as i32).outer(&frame)
+    } else {
+        outer
+    };
+
+    // Map between the monitors' work areas
+    let work = wmcli.work_area();
+    let from = src.rect.intersect(&work).unwrap_or(src.rect);
+    let to = dst.rect.intersect(&work).unwrap_or(dst.rect);
+    let target = outer.map(&from, &to);
+    let inner = target.inner(&frame);
+    wmcli.move_resize_win(
+        win,
+        None,
+        Some(target.x),
+        Some(target.y),
+        Some(inner.w.max(1) as u32),
+        Some(inner.h.max(1) as u32),
+    )?;
+    if maximized {
+        wmcli.maximize_win(win)?;
+    }
+    if fullscreen {
+        wmcli.set_win_state(win, WinStateAction::Add, WinState::Fullscreen, None)?;
+    }
+    Ok(())
 }
 
-/// Shape the given window or active window if not given without moving it.
-fn shape_win(
-    wmcli: &WindowManager, win: u32, w: u32, h: u32, bw: u32, bh: u32, shape: WinShape,
-) -> WindowManagerResult<(Option<u32>, Option<u32>, Option<u32>)> {
-    // Notes
-    // * return values from this func should not include the border sizes
-    Ok(match shape {
-        WinShape::Max => {
-            wmcli.maximize_win(win)?;
-            (None, None, None)
-        },
-        WinShape::UnMax => {
-            wmcli.unmaximize_win(win)?;
-            (None, None, None)
-        },
-        _ => {
-            wmcli.unmaximize_win(win)?;
-
-            // Pre-calculations
-            let fw = wmcli.work_width - bw; // total width - border
-            let fh = wmcli.work_height - bh; // total height - border
-            let hw = wmcli.work_width / 2 - bw; // total half width - border
-            let hh = wmcli.work_height / 2 - bh; // total half height - border
-
-            let (w, h) = match shape {
-                // Grow the existing dimensions by 1% until full size
-                WinShape::Grow => {
-                    let mut w = ((w - bw) as f32 * 1.01) as u32 + bw;
-                    if w >= fw {
-                        w = fw
-                    }
-                    let mut h = ((h - bh) as f32 * 1.01) as u32 + bh;
-                    if h >= fh {
-                        h = fh
-                    }
-                    (Some(w), Some(h))
-                },
-
-                // Half width x full height
-                WinShape::Halfw => (Some(hw), Some(fh)),
-
-                // Full width x half height
-                WinShape::Halfh => (Some(fw), Some(hh)),
-
-                // Half width x half height
-                WinShape::Small => (Some(hw), Some(hh)),
-
-                // 3/4 short side x 4x3 sized long size
-                WinShape::Medium => {
-                    let (w, h) = if wmcli.work_height < wmcli.work_width {
-                        let h = fh as f32 * 0.75;
-                        ((h * 4.0 / 3.0) as u32, h as u32)
-                    } else {
-                        let w = fw as f32 * 0.75;
-                        (w as u32, (w * 4.0 / 3.0) as u32)
-                    };
-                    (Some(w), Some(h))
-                },
-
-                // Full short side x 4x3 sized long size
-                WinShape::Large => {
-                    let (w, h) = if wmcli.work_height < wmcli.work_width {
-                        ((fh as f32 * 4.0 / 3.0) as u32, fh)
-                    } else {
-                        (fw, (fw as f32 * 4.0 / 3.0) as u32)
-                    };
-                    (Some(w), Some(h))
-                },
-
-                // Shrink the existing dimensions by 1% down to no smaller than 100x100
-                WinShape::Shrink => {
-                    // Remove the border before calculations are done then re-include
-                    let mut w = (w - bw) as f32 * 0.99;
-                    if w < 100.0 {
-                        w = 100.0
-                    }
-                    let mut h = (h - bh) as f32 * 0.99;
-                    if h < 100.0 {
-                        h = 100.0
-                    }
-                    (Some(w as u32 + bw), Some(h as u32 + bh))
-                },
-
-                // Don't change anything by default
-                _ => (None, None),
-            };
-            (Some(WinGravity::Center.into()), w, h)
+/// Get the x11 information including the window manager's name, if there is a composite
+/// manager running and what the screen and work screen sizes are along with the window's details.
+///
+/// ### Arguments
+/// * `win` - id of the window to inspect else the active window will be used
+///
+/// ### Examples
+/// ```ignore
+/// use libewmh::prelude::*;
+/// let (wm, win) = libewmh::window::info(None).unwrap();
+/// ```
+pub fn info(win: Option<u32>) -> WindowManagerResult<(WmInfo, WinInfo)> {
+    let wmcli = WindowManager::connect()?;
+    let (_, name) = wmcli.winmgr()?;
+    let win = match win {
+        Some(win) => win,
+        None => wmcli.active_win()?,
+    };
+    let wm = WmInfo {
+        name,
+        composite_manager: wmcli.composite_manager()?,
+        root: wmcli.root(),
+        work_area: wmcli.work_area(),
+        width: wmcli.width(),
+        height: wmcli.height(),
+        desktops: wmcli.desktops()?,
+        monitors: wmcli.monitors().unwrap_or_default(),
+    };
+    Ok((wm, wmcli.win_info(win)?))
+}
+
+/// Get the windows the window manager is managing and their essential properties
+///
+/// ### Arguments
+/// * `all` - when set to true will list all x11 windows not just those the window manager lists
+/// * `selector` - only list the windows matching the given selector
+///
+/// ### Examples
+/// ```ignore
+/// use libewmh::prelude::*;
+/// let wins = libewmh::window::list(false, None).unwrap();
+/// ```
+pub fn list(all: bool, selector: Option<&WinSelector>) -> WindowManagerResult<Vec<WinInfo>> {
+    let wmcli = WindowManager::connect()?;
+    let mut wins = vec![];
+    for win in wmcli.get_windows(all)? {
+        if selector.map(|x| x.is_match(&wmcli, win.id)).unwrap_or(true) {
+            // Windows may close while being listed and most root children lack the EWMH properties
+            match wmcli.win_info(win.id) {
+                Ok(info) => wins.push(info),
+                Err(err) => debug!("list: skipping id: {}, {}", win.id, err),
+            }
+        }
+    }
+    Ok(wins)
+}
+
+/// Determine the area to shape and position the window within. This is the portion of the work
+/// area on the requested monitor else on the monitor containing the window's center. When RandR
+/// isn't available the whole work area is used.
+pub(crate) fn work_area(wmcli: &WindowManager, monitor: Option<&str>, win: &Rect) -> WindowManagerResult<Rect> {
+    let work = wmcli.work_area();
+    let monitor = match monitor {
+        Some(monitor) => Some(wmcli.monitor(monitor)?),
+        None => {
+            let monitors = wmcli.monitors().unwrap_or_default();
+            monitors
+                .iter()
+                .find(|x| x.rect.contains(win.center()))
+                .or_else(|| monitors.iter().find(|x| x.primary))
+                .cloned()
         },
-    })
+    };
+    Ok(monitor.and_then(|x| x.rect.intersect(&work)).unwrap_or(work))
 }
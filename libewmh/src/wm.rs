@@ -12,15 +12,22 @@
 //! be shaped and positioned on the screen in an ergonomic way; however `WindowManager` could be used
 //! for a variety of reasons.
 use crate::{
-    atoms::AtomCollection, model::*, window::Window, ErrorWrapper, WindowManagerError, WindowManagerResult,
+    atoms::AtomCollection, event::Events, model::*, window::Window, ErrorWrapper, Rect, WindowManagerError,
+    WindowManagerResult,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    str,
+    sync::{Arc, Mutex},
 };
-use std::{collections::HashMap, str, sync::Arc};
 use tracing::{debug, trace};
 
 use x11rb::{
     connection::Connection,
-    protocol::xproto::{
-        self, Atom, AtomEnum, ClientMessageEvent, ConnectionExt as _, EventMask, GetPropertyReply,
+    protocol::{
+        randr::ConnectionExt as _,
+        xproto::{self, Atom, AtomEnum, ClientMessageEvent, ConnectionExt as _, EventMask, GetPropertyReply},
+        Event,
     },
     rust_connection::RustConnection,
 };
@@ -33,22 +40,32 @@ pub const MOVE_RESIZE_WINDOW_Y: MoveResizeWindowFlags = 1 << 9;
 pub const MOVE_RESIZE_WINDOW_WIDTH: MoveResizeWindowFlags = 1 << 10;
 pub const MOVE_RESIZE_WINDOW_HEIGHT: MoveResizeWindowFlags = 1 << 11;
 
-pub type WindowStateAction = u32;
-pub const WINDOW_STATE_ACTION_REMOVE: WindowStateAction = 0;
-pub const WINDOW_STATE_ACTION_ADD: WindowStateAction = 1;
+#[deprecated(note = "use WinStateAction instead")]
+pub type WindowStateAction = WinStateAction;
+#[deprecated(note = "use WinStateAction::Remove instead")]
+pub const WINDOW_STATE_ACTION_REMOVE: WinStateAction = WinStateAction::Remove;
+#[deprecated(note = "use WinStateAction::Add instead")]
+pub const WINDOW_STATE_ACTION_ADD: WinStateAction = WinStateAction::Add;
+
+/// Desktop value used to show a window on all desktops
+pub const ALL_DESKTOPS: u32 = 0xFFFFFFFF;
+
+// Source indication used in client messages to identify the requester. Pagers and other tools
+// acting on behalf of the user, as opposed to applications, should use this value.
+pub(crate) const SOURCE_INDICATION_PAGER: u32 = 2;
 
 /// Window Manager control implements the EWMH protocol using x11rb to provide a simplified access
 /// layer to EWHM compatible window managers.
 pub struct WindowManager {
-    conn: Arc<RustConnection>,     // x11 connection
-    pub atoms: AtomCollection,     // atom cache
-    supported: HashMap<u32, bool>, // cache for supported functions
-    screen: usize,                 // screen number
-    root: u32,                     // root window id
-    width: u32,                    // screen width
-    height: u32,                   // screen height
-    work_width: u32,               // screen height
-    work_height: u32,              // screen height
+    conn: Arc<RustConnection>,      // x11 connection
+    pub atoms: AtomCollection,      // atom cache
+    supported: HashMap<u32, bool>,  // cache for supported functions
+    screen: usize,                  // screen number
+    root: u32,                      // root window id
+    width: u32,                     // screen width
+    height: u32,                    // screen height
+    work_area: Rect,                // current desktop's work area
+    queued: Mutex<VecDeque<Event>>, // events read while waiting for a reply e.g. server time
 }
 
 pub struct GetPropertyResult {
@@ -92,14 +109,12 @@ impl WindowManager {
             root,
             width,
             height,
-            work_width: Default::default(),
-            work_height: Default::default(),
+            work_area: Default::default(),
+            queued: Default::default(),
         };
 
         // Get the work area
-        let (width, height) = wmcli.workarea()?;
-        wmcli.work_width = width as u32;
-        wmcli.work_height = height as u32;
+        wmcli.refresh_workarea()?;
 
         debug!("connect: screen: {}, root: {}, w: {}, h: {}", screen, root, width, height);
         Ok(wmcli)
@@ -144,14 +159,34 @@ impl WindowManager {
         self.height
     }
 
+    /// Get the current desktop's work area which is the screen minus any taskbars or panels. The
+    /// origin is offset by any panels along the left or top edges.
+    pub fn work_area(&self) -> Rect {
+        self.work_area
+    }
+
     /// Get screen work width which is the full width minus any taskbars
     pub fn work_width(&self) -> u32 {
-        self.work_width
+        self.work_area.w as u32
     }
 
     /// Get screen work height which is the full width minus any taskbars
     pub fn work_height(&self) -> u32 {
-        self.work_height
+        self.work_area.h as u32
+    }
+
+    /// Refresh the cached work area for the current desktop. The work area can change when
+    /// switching desktops or when panels are added, removed or resized.
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let mut wmcli = wmcli::connect().unwrap();
+    /// wmcli.refresh_workarea().unwrap();
+    /// ```
+    pub fn refresh_workarea(&mut self) -> WindowManagerResult<()> {
+        self.work_area = self.workarea()?;
+        Ok(())
     }
 
     fn _get_window_property<A: Into<Atom>, B: Into<Atom>>(
@@ -178,6 +213,88 @@ impl WindowManager {
         self.get_root_property(self.atoms._NET_ACTIVE_WINDOW, AtomEnum::WINDOW).try_into()
     }
 
+    /// Close the window gracefully
+    ///
+    /// Uses `_NET_CLOSE_WINDOW` when the window manager supports it else falls back on sending
+    /// the ICCCM `WM_DELETE_WINDOW` protocol message directly to the window.
+    ///
+    /// ### Arguments
+    /// * `win` - id of the window to manipulate
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// wmcli.close_win(12345).unwrap();
+    /// ```
+    pub fn close_win(&self, win: xproto::Window) -> WindowManagerResult<()> {
+        let time = self.server_time()?;
+
+        // Defined as: _NET_CLOSE_WINDOW, timestamp, source indication
+        if self.supported(self.atoms._NET_CLOSE_WINDOW) {
+            self.send_event(ClientMessageEvent::new(
+                32,
+                win,
+                self.atoms._NET_CLOSE_WINDOW,
+                [time, SOURCE_INDICATION_PAGER, 0, 0, 0],
+            ))?;
+            debug!("close_win: _NET_CLOSE_WINDOW id: {}", win);
+            return Ok(());
+        }
+
+        // Defined as: WM_PROTOCOLS, ATOM[]/32 listing WM_DELETE_WINDOW if the client supports it
+        let reply =
+            self.conn.get_property(false, win, self.atoms.WM_PROTOCOLS, AtomEnum::ATOM, 0, u32::MAX)?.reply()?;
+        if !reply.value32().map(|mut x| x.any(|x| x == self.atoms.WM_DELETE_WINDOW)).unwrap_or(false) {
+            return Err(WindowManagerError::WinDeleteNotSupported(win).into());
+        }
+
+        // The ICCCM message goes directly to the client rather than through the root window
+        let msg = ClientMessageEvent::new(
+            32,
+            win,
+            self.atoms.WM_PROTOCOLS,
+            [self.atoms.WM_DELETE_WINDOW, time, 0, 0, 0],
+        );
+        self.conn.send_event(false, win, EventMask::NO_EVENT, msg)?.check()?;
+        self.conn.flush()?;
+        debug!("close_win: WM_DELETE_WINDOW id: {}", win);
+        Ok(())
+    }
+
+    /// Activate the window giving it focus and raising it. If the window is on another desktop
+    /// then switch to that desktop first.
+    ///
+    /// ### Arguments
+    /// * `win` - id of the window to manipulate
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// wmcli.activate_win(12345).unwrap();
+    /// ```
+    pub fn activate_win(&self, win: xproto::Window) -> WindowManagerResult<()> {
+        // Windows shown on all desktops report a negative desktop
+        if let Ok(desktop) = self.win_desktop(win) {
+            if desktop >= 0 && desktop as u32 != self.current_desktop()? {
+                self.set_current_desktop(desktop as u32)?;
+            }
+        }
+
+        // Defined as: _NET_ACTIVE_WINDOW, source indication, timestamp, requestor's currently active window
+        let time = self.server_time()?;
+        let active = self.active_win().unwrap_or(x11rb::NONE);
+        self.send_event(ClientMessageEvent::new(
+            32,
+            win,
+            self.atoms._NET_ACTIVE_WINDOW,
+            [SOURCE_INDICATION_PAGER, time, active, 0, 0],
+        ))?;
+        debug!("activate_win: id: {}, time: {}", win, time);
+        Ok(())
+    }
+
     /// Check if a composit manager is running
     pub fn composite_manager(&self) -> WindowManagerResult<bool> {
         // Defined as: _NET_WM_CM_Sn
@@ -191,12 +308,91 @@ impl WindowManager {
         Ok(result)
     }
 
+    /// Get the current desktop index
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// let desktop = wmcli.current_desktop().unwrap();
+    /// ```
+    pub fn current_desktop(&self) -> WindowManagerResult<u32> {
+        // Defined as: _NET_CURRENT_DESKTOP desktop, CARDINAL/32
+        self.get_root_property(self.atoms._NET_CURRENT_DESKTOP, AtomEnum::CARDINAL).try_into()
+    }
+
+    /// Get the desktop names
+    ///
+    /// The window manager may list fewer names than there are desktops in which case the remaining
+    /// desktops are simply unnamed.
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// let names = wmcli.desktop_names().unwrap();
+    /// ```
+    pub fn desktop_names(&self) -> WindowManagerResult<Vec<String>> {
+        // Defined as: _NET_DESKTOP_NAMES, UTF8_STRING[]
+        // which means the value is a list of null terminated UTF8 strings one per desktop.
+        let reply = self
+            .conn
+            .get_property(false, self.root, self.atoms._NET_DESKTOP_NAMES, self.atoms.UTF8_STRING, 0, u32::MAX)?
+            .reply()?;
+
+        // Drop the final null terminator so that it doesn't produce an empty trailing name
+        let value = reply.value.strip_suffix(&[0]).unwrap_or(&reply.value);
+        if value.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut names = vec![];
+        for name in value.split(|x| *x == 0) {
+            names.push(str::from_utf8(name)?.to_owned());
+        }
+        debug!("desktop_names: {:?}", names);
+        Ok(names)
+    }
+
     /// Get number of desktops
     pub fn desktops(&self) -> WindowManagerResult<u32> {
         // Defined as: _NET_NUMBER_OF_DESKTOPS, CARDINAL/32
         self.get_root_property(self.atoms._NET_NUMBER_OF_DESKTOPS, AtomEnum::CARDINAL).try_into()
     }
 
+    /// Get a blocking stream of typed window manager events e.g. windows opening, closing, moving
+    /// or the active window and desktop changing.
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// for event in wmcli.events().unwrap() {
+    ///     println!("{:?}", event.unwrap());
+    /// }
+    /// ```
+    pub fn events(&self) -> WindowManagerResult<Events<'_>> {
+        Events::new(self)
+    }
+
+    /// Forcibly close the window by having the X server kill its client connection. This is meant
+    /// for hung clients that no longer respond to a graceful close.
+    ///
+    /// ### Arguments
+    /// * `win` - id of the window to manipulate
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// wmcli.kill_win(12345).unwrap();
+    /// ```
+    pub fn kill_win(&self, win: xproto::Window) -> WindowManagerResult<()> {
+        self.conn.kill_client(win)?.check()?;
+        self.conn.flush()?;
+        debug!("kill_win: id: {}", win);
+        Ok(())
+    }
+
     /// Maximize the window both horizontally and vertiacally
     ///
     /// ### Arguments
@@ -209,29 +405,66 @@ impl WindowManager {
     /// wmcli.maximize_win(12345).unwrap();
     /// ```
     pub fn maximize_win(&self, win: xproto::Window) -> WindowManagerResult<()> {
-        self.send_event(ClientMessageEvent::new(
-            32,
-            win,
-            self.atoms._NET_WM_STATE,
-            [
-                WINDOW_STATE_ACTION_ADD,
-                self.atoms._NET_WM_STATE_MAXIMIZED_HORZ,
-                self.atoms._NET_WM_STATE_MAXIMIZED_VERT,
-                0,
-                0,
-            ],
-        ))?;
+        self.set_win_state(win, WinStateAction::Add, WinState::MaxHorz, Some(WinState::MaxVert))?;
         debug!("maximize: id: {}", win);
         Ok(())
     }
 
+    /// Get the monitor by name, index or `primary` for the primary monitor
+    ///
+    /// ### Arguments
+    /// * `monitor` - name, index or `primary`
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// let monitor = wmcli.monitor("primary").unwrap();
+    /// ```
+    pub fn monitor(&self, monitor: &str) -> WindowManagerResult<Monitor> {
+        let monitors = self.monitors()?;
+        let found = match monitor {
+            // Not all setups flag a primary monitor so fall back on the first
+            "primary" => monitors.iter().find(|x| x.primary).or(monitors.first()),
+            _ => match monitor.parse::<usize>() {
+                Ok(i) => monitors.get(i),
+                Err(_) => monitors.iter().find(|x| x.name == monitor),
+            },
+        };
+        found.cloned().ok_or_else(|| WindowManagerError::InvalidMonitor(monitor.to_string()).into())
+    }
+
+    /// Get the active monitors using the RandR extension
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// let monitors = wmcli.monitors().unwrap();
+    /// ```
+    pub fn monitors(&self) -> WindowManagerResult<Vec<Monitor>> {
+        let reply = self.conn.randr_get_monitors(self.root, true)?.reply()?;
+        let mut monitors = vec![];
+        for info in reply.monitors {
+            let name = self.conn.get_atom_name(info.name)?.reply()?;
+            let monitor = Monitor {
+                name: String::from_utf8_lossy(&name.name).into_owned(),
+                primary: info.primary,
+                rect: Rect::new(info.x as i32, info.y as i32, info.width as i32, info.height as i32),
+            };
+            debug!("monitors: name: {}, primary: {}, rect: {}", monitor.name, monitor.primary, monitor.rect);
+            monitors.push(monitor);
+        }
+        Ok(monitors)
+    }
+
     /// Move and resize the given window
     ///
     /// ### Arguments
     /// * `win` - id of the window to manipulate
     /// * `gravity` - gravity to use when resizing the window, defaults to NorthWest
-    /// * `x` - x coordinate to use for the window during positioning
-    /// * `y` - y coordinate to use for the window during positioning
+    /// * `x` - x coordinate to use for the window during positioning, may be negative
+    /// * `y` - y coordinate to use for the window during positioning, may be negative
     /// * `w` - width to resize the window to
     /// * `h` - height to resize the window to
     ///
@@ -242,9 +475,57 @@ impl WindowManager {
     /// wmcli.move_resize_win(12345, None, Some(0), Some(0), Some(500), Some(500)).unwrap();
     /// ```
     pub fn move_resize_win(
-        &self, win: xproto::Window, gravity: Option<u32>, x: Option<u32>, y: Option<u32>, w: Option<u32>,
+        &self, win: xproto::Window, gravity: Option<u32>, x: Option<i32>, y: Option<i32>, w: Option<u32>,
         h: Option<u32>,
     ) -> WindowManagerResult<()> {
+        self.send_event(self.move_resize_msg(win, gravity, x, y, w, h))?;
+        debug!("move_resize_win: id: {}, g: {:?}, x: {:?}, y: {:?}, w: {:?}, h: {:?}", win, gravity, x, y, w, h);
+        Ok(())
+    }
+
+    /// Move and resize the given windows together. All of the windows are sent their move resize
+    /// before any are sent again for Xfwm4 so that the windows change at the same time.
+    ///
+    /// ### Arguments
+    /// * `wins` - id of each window to manipulate along with the x, y coordinates and width and
+    ///   height to give it as passed to `move_resize_win`
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// let (left, right) = (Rect::new(0, 0, 500, 500), Rect::new(500, 0, 500, 500));
+    /// wmcli.move_resize_wins(&[(12345, left), (67890, right)]).unwrap();
+    /// ```
+    pub fn move_resize_wins(&self, wins: &[(xproto::Window, Rect)]) -> WindowManagerResult<()> {
+        let msgs = wins
+            .iter()
+            .map(|(win, rect)| {
+                let (w, h) = (rect.w.max(1) as u32, rect.h.max(1) as u32);
+                self.move_resize_msg(*win, None, Some(rect.x), Some(rect.y), Some(w), Some(h))
+            })
+            .collect::<Vec<_>>();
+
+        // Same as send_event but batching the second send that Xfwm4 needs
+        let mask = EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY;
+        for pass in 0..2 {
+            if pass > 0 {
+                std::thread::sleep(std::time::Duration::from_millis(50));
+            }
+            for msg in &msgs {
+                self.conn.send_event(false, self.root, mask, *msg)?.check()?;
+            }
+            self.conn.flush()?;
+        }
+        debug!("move_resize_wins: {:?}", wins);
+        Ok(())
+    }
+
+    // Construct the move resize message for the given window
+    fn move_resize_msg(
+        &self, win: xproto::Window, gravity: Option<u32>, x: Option<i32>, y: Option<i32>, w: Option<u32>,
+        h: Option<u32>,
+    ) -> ClientMessageEvent {
         // Construct the move resize message
         //
         // Gravity is defined as the lower byte of the move resize flags 32bit value
@@ -270,14 +551,21 @@ impl WindowManager {
             flags |= MOVE_RESIZE_WINDOW_HEIGHT;
         }
 
-        self.send_event(ClientMessageEvent::new(
+        // The message's fields are all CARDINALs but window managers read x and y back as signed
+        // values so negative coordinates are encoded as their two's complement bit pattern.
+        ClientMessageEvent::new(
             32,
             win,
             self.atoms._NET_MOVERESIZE_WINDOW,
-            [flags, x.unwrap_or(0), y.unwrap_or(0), w.unwrap_or(0), h.unwrap_or(0)],
-        ))?;
+            [flags, x.unwrap_or(0) as u32, y.unwrap_or(0) as u32, w.unwrap_or(0), h.unwrap_or(0)],
+        )
+    }
 
-        debug!("move_resize_win: id: {}, g: {:?}, x: {:?}, y: {:?}, w: {:?}, h: {:?}", win, gravity, x, y, w, h);
+    // Select the events the given window should report to this client
+    pub(crate) fn select_input(&self, win: xproto::Window, mask: EventMask) -> WindowManagerResult<()> {
+        let aux = xproto::ChangeWindowAttributesAux::new().event_mask(mask);
+        self.conn.change_window_attributes(win, &aux)?.check()?;
+        debug!("select_input: win: {}, mask: {:?}", win, mask);
         Ok(())
     }
 
@@ -297,7 +585,7 @@ impl WindowManager {
     /// ```
     pub fn send_event(&self, msg: ClientMessageEvent) -> WindowManagerResult<()> {
         let mask = EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY;
-        self.conn.send_event(false, self.root, mask, &msg)?.check()?;
+        self.conn.send_event(false, self.root, mask, msg)?.check()?;
         self.conn.flush()?;
         debug!("send_event: win: {}", msg.window);
 
@@ -306,13 +594,208 @@ impl WindowManager {
         // unfortunate shortcoming we have to send the event a second time.
         if msg.type_ == self.atoms._NET_MOVERESIZE_WINDOW {
             std::thread::sleep(std::time::Duration::from_millis(50));
-            self.conn.send_event(false, self.root, mask, &msg)?.check()?;
+            self.conn.send_event(false, self.root, mask, msg)?.check()?;
             self.conn.flush()?;
             debug!("send_event: win: {}", msg.window);
         }
         Ok(())
     }
 
+    // Block until the next event is received from the X server returning any events queued by
+    // other calls first
+    pub(crate) fn wait_for_event(&self) -> WindowManagerResult<Event> {
+        if let Some(event) = self.queued.lock().unwrap().pop_front() {
+            return Ok(event);
+        }
+        self.conn.flush()?;
+        Ok(self.conn.wait_for_event()?)
+    }
+
+    /// Get the current X server time
+    ///
+    /// X11 has no request to read the server time directly so instead we append a zero length
+    /// property to a throw away window and take the timestamp from the resulting `PropertyNotify`.
+    /// Any other events received in the meantime are queued for `events` to return.
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// let time = wmcli.server_time().unwrap();
+    /// ```
+    pub fn server_time(&self) -> WindowManagerResult<u32> {
+        let win = self.conn.generate_id()?;
+        let aux = xproto::CreateWindowAux::new().event_mask(EventMask::PROPERTY_CHANGE);
+        self.conn.create_window(
+            0,
+            win,
+            self.root,
+            -1,
+            -1,
+            1,
+            1,
+            0,
+            xproto::WindowClass::INPUT_ONLY,
+            x11rb::COPY_FROM_PARENT,
+            &aux,
+        )?;
+        self.conn.change_property(xproto::PropMode::APPEND, win, AtomEnum::WM_NAME, AtomEnum::STRING, 8, 0, &[])?;
+        self.conn.flush()?;
+
+        // Wait for the notification of our own property change keeping any others for later
+        let time = loop {
+            match self.conn.wait_for_event()? {
+                Event::PropertyNotify(event) if event.window == win => break event.time,
+                event => self.queued.lock().unwrap().push_back(event),
+            }
+        };
+        self.conn.destroy_window(win)?;
+        self.conn.flush()?;
+        debug!("server_time: {}", time);
+        Ok(time)
+    }
+
+    /// Switch to the given desktop
+    ///
+    /// ### Arguments
+    /// * `desktop` - index of the desktop to switch to
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// wmcli.set_current_desktop(1).unwrap();
+    /// ```
+    pub fn set_current_desktop(&self, desktop: u32) -> WindowManagerResult<()> {
+        // Defined as: _NET_CURRENT_DESKTOP, new_index, timestamp
+        if desktop >= self.desktops()? {
+            return Err(WindowManagerError::InvalidDesktop(desktop.to_string()).into());
+        }
+        let time = self.server_time()?;
+        self.send_event(ClientMessageEvent::new(
+            32,
+            self.root,
+            self.atoms._NET_CURRENT_DESKTOP,
+            [desktop, time, 0, 0, 0],
+        ))?;
+        debug!("set_current_desktop: desktop: {}, time: {}", desktop, time);
+        Ok(())
+    }
+
+    /// Change the number of desktops
+    ///
+    /// When reducing the number of desktops the window manager is expected to move any windows on
+    /// the removed desktops to the new last desktop.
+    ///
+    /// ### Arguments
+    /// * `desktops` - number of desktops there should be
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// wmcli.set_desktops(4).unwrap();
+    /// ```
+    pub fn set_desktops(&self, desktops: u32) -> WindowManagerResult<()> {
+        // Defined as: _NET_NUMBER_OF_DESKTOPS, new_number_of_desktops
+        if desktops == 0 {
+            return Err(WindowManagerError::InvalidDesktop(desktops.to_string()).into());
+        }
+        self.send_event(ClientMessageEvent::new(
+            32,
+            self.root,
+            self.atoms._NET_NUMBER_OF_DESKTOPS,
+            [desktops, 0, 0, 0, 0],
+        ))?;
+        debug!("set_desktops: desktops: {}", desktops);
+        Ok(())
+    }
+
+    /// Move the window to the given desktop
+    ///
+    /// ### Arguments
+    /// * `win` - id of the window to manipulate
+    /// * `desktop` - index of the desktop to move the window to or `ALL_DESKTOPS` to show it on all
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// wmcli.set_win_desktop(12345, 1).unwrap();
+    /// ```
+    pub fn set_win_desktop(&self, win: xproto::Window, desktop: u32) -> WindowManagerResult<()> {
+        // Defined as: _NET_WM_DESKTOP, new_desktop, source indication
+        if desktop != ALL_DESKTOPS && desktop >= self.desktops()? {
+            return Err(WindowManagerError::InvalidDesktop(desktop.to_string()).into());
+        }
+        self.send_event(ClientMessageEvent::new(
+            32,
+            win,
+            self.atoms._NET_WM_DESKTOP,
+            [desktop, SOURCE_INDICATION_PAGER, 0, 0, 0],
+        ))?;
+        debug!("set_win_desktop: id: {}, desktop: {}", win, desktop);
+        Ok(())
+    }
+
+    /// Add, remove or toggle one or two window states in a single operation
+    ///
+    /// ### Arguments
+    /// * `win` - id of the window to manipulate
+    /// * `action` - whether to add, remove or toggle the states
+    /// * `state1` - first state to change
+    /// * `state2` - optional second state to change at the same time e.g. MaxHorz and MaxVert
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// wmcli.set_win_state(12345, WinStateAction::Toggle, WinState::Above, None).unwrap();
+    /// ```
+    pub fn set_win_state(
+        &self, win: xproto::Window, action: WinStateAction, state1: WinState, state2: Option<WinState>,
+    ) -> WindowManagerResult<()> {
+        // Defined as: _NET_WM_STATE, action, first property, second property, source indication
+        let first = state1.atom(&self.atoms);
+        let second = state2.as_ref().map(|x| x.atom(&self.atoms)).unwrap_or(0);
+        debug!("set_win_state: id: {}, action: {}, state1: {}, state2: {:?}", win, action, state1, state2);
+        self.send_event(ClientMessageEvent::new(
+            32,
+            win,
+            self.atoms._NET_WM_STATE,
+            [action.into(), first, second, SOURCE_INDICATION_PAGER, 0],
+        ))
+    }
+
+    /// Set the window opacity for compositing managers to honour
+    ///
+    /// ### Arguments
+    /// * `win` - id of the window to manipulate
+    /// * `opacity` - opacity from fully transparent at 0.0 to fully opaque at 1.0
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// wmcli.set_win_opacity(12345, 0.9).unwrap();
+    /// ```
+    pub fn set_win_opacity(&self, win: xproto::Window, opacity: f32) -> WindowManagerResult<()> {
+        // Defined as: _NET_WM_WINDOW_OPACITY, CARDINAL/32 scaled so that 0xffffffff is fully opaque
+        let value = (opacity.clamp(0.0, 1.0) as f64 * u32::MAX as f64) as u32;
+        self.conn.change_property(
+            xproto::PropMode::REPLACE,
+            win,
+            self.atoms._NET_WM_WINDOW_OPACITY,
+            AtomEnum::CARDINAL,
+            32,
+            1,
+            &value.to_ne_bytes(),
+        )?;
+        self.conn.flush()?;
+        debug!("set_win_opacity: id: {}, opacity: {}", win, opacity);
+        Ok(())
+    }
+
     /// Determine if the given function is supported by the window manager
     ///
     /// ### Arguments
@@ -326,7 +809,7 @@ impl WindowManager {
     /// ```
     #[allow(dead_code)]
     pub fn supported(&self, atom: u32) -> bool {
-        self.supported.get(&atom).is_some()
+        self.supported.contains_key(&atom)
     }
 
     /// Remove the MaxVert and MaxHorz states
@@ -341,18 +824,7 @@ impl WindowManager {
     /// wmcli.unmaximize_win(12345).unwrap();
     /// ```
     pub fn unmaximize_win(&self, win: xproto::Window) -> WindowManagerResult<()> {
-        self.send_event(ClientMessageEvent::new(
-            32,
-            win,
-            self.atoms._NET_WM_STATE,
-            [
-                WINDOW_STATE_ACTION_REMOVE,
-                self.atoms._NET_WM_STATE_MAXIMIZED_HORZ,
-                self.atoms._NET_WM_STATE_MAXIMIZED_VERT,
-                0,
-                0,
-            ],
-        ))?;
+        self.set_win_state(win, WinStateAction::Remove, WinState::MaxHorz, Some(WinState::MaxVert))?;
         debug!("unmaximize: id: {}", win);
         Ok(())
     }
@@ -390,6 +862,27 @@ impl WindowManager {
         Ok(windows)
     }
 
+    /// Get the managed windows in stacking order from the bottom most to the top most window. Most
+    /// window managers raise windows as they're activated so this doubles as the reverse of the
+    /// most recently used order.
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// let top = wmcli.stacking_order().unwrap().last().copied();
+    /// ```
+    pub fn stacking_order(&self) -> WindowManagerResult<Vec<u32>> {
+        // Defined as: _NET_CLIENT_LIST_STACKING, WINDOW[]/32
+        let reply = self
+            .conn
+            .get_property(false, self.root, self.atoms._NET_CLIENT_LIST_STACKING, AtomEnum::WINDOW, 0, u32::MAX)?
+            .reply()?;
+        let wins = reply.value32().ok_or(WindowManagerError::PropertyNotFound)?.collect::<Vec<_>>();
+        debug!("stacking_order: {:?}", wins);
+        Ok(wins)
+    }
+
     /// Get window manager's window id and name
     pub fn winmgr(&self) -> WindowManagerResult<(u32, String)> {
         let win: u32 = self.get_root_property(self.atoms._NET_SUPPORTING_WM_CHECK, AtomEnum::WINDOW).try_into()?;
@@ -397,15 +890,33 @@ impl WindowManager {
         Ok((win, name))
     }
 
-    /// Get desktop work area
+    /// Get the current desktop's work area
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// let area = wmcli.workarea().unwrap();
+    /// ```
+    pub fn workarea(&self) -> WindowManagerResult<Rect> {
+        let areas = self.workareas()?;
+
+        // Window managers may list a single work area for all desktops
+        let desktop = self.current_desktop().unwrap_or(0) as usize;
+        let area = areas.get(desktop).or(areas.first()).copied().ok_or(WindowManagerError::PropertyNotFound)?;
+        debug!("work_area: desktop: {}, area: {}", desktop, area);
+        Ok(area)
+    }
+
+    /// Get the work area of each desktop
     ///
     /// ### Examples
     /// ```ignore
     /// use libewmh::prelude::*;
     /// let wmcli = wmcli::connect().unwrap();
-    /// let (w, h) = wmcli.workarea().unwrap();
+    /// let areas = wmcli.workareas().unwrap();
     /// ```
-    pub fn workarea(&self) -> WindowManagerResult<(u16, u16)> {
+    pub fn workareas(&self) -> WindowManagerResult<Vec<Rect>> {
         // Defined as: _NET_WORKAREA, x, y, width, height CARDINAL[][4]/32
         // which means when retrieving the value via `get_property` that we need to use a `self.atoms._NET_WORKAREA`
         // request message with a `AtomEnum::CARDINAL` type response and we can use the `reply.value32()` accessor to
@@ -414,15 +925,15 @@ impl WindowManager {
             .conn
             .get_property(false, self.root, self.atoms._NET_WORKAREA, AtomEnum::CARDINAL, 0, u32::MAX)?
             .reply()?;
-        let mut values = reply.value32().ok_or(WindowManagerError::PropertyNotFound)?;
-        let x = values.next().ok_or(WindowManagerError::PropertyNotFound)?;
-        let y = values.next().ok_or(WindowManagerError::PropertyNotFound)?;
-        let w = values.next().ok_or(WindowManagerError::PropertyNotFound)?;
-        let h = values.next().ok_or(WindowManagerError::PropertyNotFound)?;
-        debug!("work_area: x: {}, y: {}, w: {}, h: {}", x, y, w, h);
-
-        // x and y are always zero so dropping them
-        Ok((w as u16, h as u16))
+        let values = reply.value32().ok_or(WindowManagerError::PropertyNotFound)?.collect::<Vec<_>>();
+        let areas = values
+            .chunks_exact(4)
+            .map(|x| Rect::new(x[0] as i32, x[1] as i32, x[2] as i32, x[3] as i32))
+            .collect::<Vec<_>>();
+        if areas.is_empty() {
+            return Err(WindowManagerError::PropertyNotFound.into());
+        }
+        Ok(areas)
     }
 
     /// Get window attribrtes
@@ -470,6 +981,28 @@ impl WindowManager {
         Ok(class)
     }
 
+    /// Get window instance which is the first part of WM_CLASS e.g. the resource name
+    ///
+    /// ### Arguments
+    /// * `win` - id of the window to manipulate
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// let instance = wmcli.win_instance(12345).unwrap();
+    /// ```
+    pub fn win_instance(&self, win: xproto::Window) -> WindowManagerResult<String> {
+        let reply =
+            self.conn.get_property(false, win, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, u32::MAX)?.reply()?;
+
+        // Extract the first null terminated string
+        let iter = reply.value.into_iter().take_while(|x| *x != 0);
+        let instance = str::from_utf8(&iter.collect::<Vec<_>>())?.to_owned();
+        debug!("win_instance: id: {}, instance: {}", win, instance);
+        Ok(instance)
+    }
+
     /// Get window desktop
     ///
     /// ### Arguments
@@ -537,6 +1070,35 @@ impl WindowManager {
         Ok((x as i32, y as i32, w as u32, h as u32))
     }
 
+    /// Get the essential window properties as a single record
+    ///
+    /// ### Arguments
+    /// * `win` - id of the window to manipulate
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// let info = wmcli.win_info(12345).unwrap();
+    /// ```
+    pub fn win_info(&self, win: xproto::Window) -> WindowManagerResult<WinInfo> {
+        let (x, y, w, h) = self.win_geometry(win)?;
+        Ok(WinInfo {
+            id: win,
+            desktop: self.win_desktop(win).unwrap_or(-1),
+            pid: self.win_pid(win).unwrap_or(-1),
+            x,
+            y,
+            w,
+            h,
+            borders: self.win_borders(win).unwrap_or((0, 0, 0, 0)),
+            typ: self.win_type(win).ok(),
+            state: self.win_state(win).unwrap_or_default(),
+            class: self.win_class(win).unwrap_or_default(),
+            name: self.win_name(win).unwrap_or_default(),
+        })
+    }
+
     /// Get window name
     ///
     /// ### Arguments
@@ -561,7 +1123,7 @@ impl WindowManager {
             .reply()?;
         if reply.type_ != x11rb::NONE {
             if let Ok(value) = str::from_utf8(&reply.value) {
-                if value != "" {
+                if !value.is_empty() {
                     debug!("win_name: using _NET_WM_VISIBLE_NAME for: {}", value);
                     return Ok(value.to_owned());
                 }
@@ -575,7 +1137,7 @@ impl WindowManager {
             .reply()?;
         if reply.type_ != x11rb::NONE {
             if let Ok(value) = str::from_utf8(&reply.value) {
-                if value != "" {
+                if !value.is_empty() {
                     debug!("win_name: using _NET_WM_NAME for: {}", value);
                     return Ok(value.to_owned());
                 }
@@ -587,7 +1149,7 @@ impl WindowManager {
             self.conn.get_property(false, win, AtomEnum::WM_NAME, AtomEnum::STRING, 0, u32::MAX)?.reply()?;
         if reply.type_ != x11rb::NONE {
             if let Ok(value) = str::from_utf8(&reply.value) {
-                if value != "" {
+                if !value.is_empty() {
                     debug!("win_name: using WM_NAME for: {}", value);
                     return Ok(value.to_owned());
                 }
@@ -660,6 +1222,31 @@ impl WindowManager {
         Ok(states)
     }
 
+    /// Get window role which session aware applications set to tell their windows apart
+    ///
+    /// ### Arguments
+    /// * `win` - id of the window to manipulate
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// use libewmh::prelude::*;
+    /// let wmcli = wmcli::connect().unwrap();
+    /// let role = wmcli.win_role(12345).unwrap();
+    /// ```
+    pub fn win_role(&self, win: xproto::Window) -> WindowManagerResult<String> {
+        // Defined as: WM_WINDOW_ROLE, STRING
+        let reply = self
+            .conn
+            .get_property(false, win, self.atoms.WM_WINDOW_ROLE, AtomEnum::STRING, 0, u32::MAX)?
+            .reply()?;
+        if reply.type_ == x11rb::NONE {
+            return Err(WindowManagerError::PropertyNotFound.into());
+        }
+        let role = str::from_utf8(&reply.value)?.trim_end_matches('\0').to_owned();
+        debug!("win_role: id: {}, role: {}", win, role);
+        Ok(role)
+    }
+
     /// Get window type
     ///
     /// ### Arguments
@@ -10,25 +10,106 @@
 //! ### Shape a window
 //! Shape the active window using the pre-defined `small` shape which is a quarter of the screen.
 //! ```bash
-//! wmcli shape small
+//! wmcli window shape small
 //! ```
 //!
 //! ### Move a window
 //! Move the active window to the bottom left corner of the screen.
 //! ```bash
-//! wmcli move bottom-left
+//! wmcli window move bottom-left
 //! ```
 //!
 //! ### Place a window
 //! Shape the active window using the pre-defined `small` shape which is a quarter of the screen
 //! and then position it in the bottom left corner of the screen.
 //! ```bash
-//! wmcli place small bottom-left
+//! wmcli window place small bottom-left
 //! ```
-use std::env;
+//!
+//! ### Place a window on a grid
+//! Divide the work area into a 3x2 grid and place the active window over the top two left cells.
+//! ```bash
+//! wmcli window place --grid 3x2 --cell 0,0 --span 2x1
+//! ```
+//!
+//! ### Watch events
+//! Print focus and title changes as JSON lines for a status bar to consume.
+//! ```bash
+//! wmcli watch --events focus,title
+//! ```
+//!
+//! ### Apply rules to new windows
+//! Run in the background applying the `[[rules]]` of `~/.config/wmcli/config.toml` to windows as
+//! they open e.g. placing, moving to a desktop, setting states and opacity.
+//! ```bash
+//! wmcli daemon &
+//! ```
+//!
+//! ### Arrange windows
+//! Tile the normal windows of the current desktop once with a master window and a stack beside it.
+//! ```bash
+//! wmcli arrange tile
+//! ```
+//!
+//! ### Focus by direction
+//! Activate the nearest window to the left of the active window.
+//! ```bash
+//! wmcli window focus left
+//! ```
+//!
+//! ### Cycle windows
+//! Cycle through the terminals on the current desktop in most recently used order.
+//! ```bash
+//! wmcli window focus next --class xterm
+//! ```
+//!
+//! ### Swap windows
+//! Swap the position and size of the active window with its neighbour to the right.
+//! ```bash
+//! wmcli window swap right
+//! ```
+//!
+//! ### Save and restore layouts
+//! Save the desktop, geometry and state of every window then restore them after restarting the
+//! window manager.
+//! ```bash
+//! wmcli layout save work
+//! wmcli layout restore work
+//! ```
+//!
+//! ### Tile windows
+//! Keep the normal windows of each desktop tiled with a master window taking 60% of the width and
+//! 8 pixel gaps, using a grid on the second desktop.
+//! ```bash
+//! wmcli tile-daemon --layout master-stack,grid --ratio 0.6 --gap 8 &
+//! ```
+//!
+//! ### Geometry of a window
+//! Resize the active window to the right half of the work area using an X style geometry where
+//! sizes and offsets may be given in pixels or as percentages and `-` offsets anchor to the far edge.
+//! ```bash
+//! wmcli window geometry 50%x100%+50%+0
+//! ```
+use std::{
+    collections::HashMap,
+    error::Error,
+    io::Write,
+    process,
+    time::{Duration, Instant},
+};
 
-use clap::{crate_description, crate_version, Command};
-use libewmh::{window::WinOpt, WindowManager, WindowManagerError, WindowManagerResult};
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    crate_description, crate_version,
+    parser::ValueSource,
+    value_parser, Arg, ArgMatches, Command,
+};
+use libewmh::{
+    config, desktop, window, window::WinOpt, Config, DesktopInfo, Direction, GeometrySpec, Grid, Layout, Rect,
+    Snapshot, WinFilter, WinInfo, WinMatch, WinPosition, WinSelector, WinShape, WinState, WinStateAction,
+    WindowManager, WindowManagerError, WindowManagerResult, WmEvent, WmInfo,
+};
+use serde::Serialize;
 
 fn cli() -> Command {
     Command::new("wmcli")
@@ -36,16 +117,194 @@ fn cli() -> Command {
         .subcommand_required(true)
         .arg_required_else_help(true)
         .version(crate_version!())
+        .arg(
+            Arg::new("monitor")
+                .long("monitor")
+                .short('m')
+                .value_name("MONITOR")
+                .global(true)
+                .help("Monitor to shape and position relative to by name, index or primary")
+                .long_help(
+                    "Monitor to shape and position relative to by name, index or primary. Defaults to the \
+                     monitor containing the center of the window.",
+                ),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .short('o')
+                .global(true)
+                .value_parser(["table", "json", "jsonl"])
+                .default_value("table")
+                .help("Output format for listings and information"),
+        )
         .subcommand(
             Command::new("window")
                 .visible_alias("w")
                 .about("Control individual windows.")
                 .subcommand_required(true)
                 .arg_required_else_help(true)
-                .subcommand(Command::new("list").visible_alias("l").about("List out all windows"))
-                .subcommand(Command::new("move").visible_alias("m").about("Move a window"))
-                .subcommand(Command::new("shape").visible_alias("s").about("Resize a window"))
-                .subcommand(Command::new("close").visible_alias("c").about("Close a window")),
+                .arg(
+                    Arg::new("window")
+                        .long("window")
+                        .value_name("SELECTOR")
+                        .global(true)
+                        .value_parser(|x: &str| WinSelector::try_from(x))
+                        .help("Window to operate on, defaults to the active window")
+                        .long_help(
+                            "Window to operate on, defaults to the active window. A selector is a comma \
+                             separated list of filters that must all match exactly one window e.g. 12345, \
+                             class:firefox, 'name~/^vim/', pid:1234, desktop:2,type:normal",
+                        ),
+                )
+                .subcommand(Command::new("info").visible_alias("i").about("Show X11 and window information"))
+                .subcommand(
+                    Command::new("list").visible_alias("l").about("List out all windows").arg(
+                        Arg::new("all")
+                            .long("all")
+                            .short('a')
+                            .action(clap::ArgAction::SetTrue)
+                            .help("List all X11 windows not just those the window manager manages"),
+                    ),
+                )
+                .subcommand(Command::new("move").visible_alias("m").about("Move a window").arg(position_arg()))
+                .subcommand(Command::new("shape").visible_alias("s").about("Resize a window").arg(shape_arg()))
+                .subcommand(
+                    Command::new("place")
+                        .visible_alias("p")
+                        .about("Shape and move a window in a single operation or place it over grid cells")
+                        .arg(shape_arg().required(false).required_unless_present("grid"))
+                        .arg(position_arg().required(false).required_unless_present("grid"))
+                        .arg(
+                            Arg::new("grid")
+                                .long("grid")
+                                .value_name("COLSxROWS")
+                                .conflicts_with_all(["SHAPE", "POSITION"])
+                                .value_parser(|x: &str| pair(x, 'x'))
+                                .help("Divide the work area into a grid of cells e.g. 3x2"),
+                        )
+                        .arg(
+                            Arg::new("cell")
+                                .long("cell")
+                                .value_name("COL,ROW")
+                                .requires("grid")
+                                .value_parser(|x: &str| pair(x, ','))
+                                .help("Zero based grid cell to place the window in [default: 0,0]"),
+                        )
+                        .arg(
+                            Arg::new("span")
+                                .long("span")
+                                .value_name("COLSxROWS")
+                                .requires("grid")
+                                .value_parser(|x: &str| pair(x, 'x'))
+                                .help("Number of grid cells the window should span [default: 1x1]"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("static")
+                        .about("Resize and optionally move a window to exact values")
+                        .arg(Arg::new("W").required(true).value_parser(value_parser!(u32)).help("Width in pixels"))
+                        .arg(
+                            Arg::new("H").required(true).value_parser(value_parser!(u32)).help("Height in pixels"),
+                        )
+                        .arg(coord_arg("X", "Y").help("X coordinate which may be negative e.g. -1920"))
+                        .arg(coord_arg("Y", "X").help("Y coordinate which may be negative e.g. -1080")),
+                )
+                .subcommand(
+                    Command::new("geometry")
+                        .visible_alias("g")
+                        .about("Resize and move a window using an X style geometry")
+                        .arg(
+                            Arg::new("SPEC")
+                                .required(true)
+                                .allow_hyphen_values(true)
+                                .value_parser(|x: &str| GeometrySpec::try_from(x))
+                                .help("X style geometry e.g. 1200x800+0+0, 50%x100%+50%+0, 800x600-0-0"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("state")
+                        .about("Add, remove or toggle window states")
+                        .arg(
+                            Arg::new("ACTION")
+                                .required(true)
+                                .value_parser(|x: &str| WinStateAction::try_from(x))
+                                .help("State action to take i.e. add, remove or toggle"),
+                        )
+                        .arg(state_arg("STATE1").required(true))
+                        .arg(state_arg("STATE2")),
+                )
+                .subcommand(
+                    Command::new("to-desktop")
+                        .visible_alias("td")
+                        .about("Send a window to another desktop")
+                        .arg(
+                            Arg::new("DESKTOP")
+                                .required(true)
+                                .help("Index or name of the desktop, next, prev or all for all desktops"),
+                        )
+                        .arg(
+                            Arg::new("follow")
+                                .long("follow")
+                                .action(clap::ArgAction::SetTrue)
+                                .help("Switch to the desktop and activate the window after moving it"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("to-monitor")
+                        .visible_alias("tm")
+                        .about("Move a window to another monitor keeping its relative geometry")
+                        .arg(
+                            Arg::new("MONITOR")
+                                .required(true)
+                                .help("Name or index of the monitor, primary, next or prev"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("swap")
+                        .about("Swap the position and size of a window with a neighbour or another window")
+                        .arg(
+                            Arg::new("TARGET")
+                                .required(true)
+                                .help("Neighbour's direction e.g. left, right, up, down or a window selector"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("focus")
+                        .visible_alias("f")
+                        .about("Activate a window switching to its desktop if needed")
+                        .arg(
+                            Arg::new("DIRECTION")
+                                .value_parser(
+                                    PossibleValuesParser::new(["left", "right", "up", "down", "next", "prev"])
+                                        .try_map(|x| focus_dir(&x)),
+                                )
+                                .help("Activate the nearest window in the direction or cycle to the next or prev")
+                                .long_help(
+                                    "Activate the nearest window in the direction or cycle through the windows \
+                                     in most recently used order. Next activates the least recently used window \
+                                     so repeating it visits every window while prev returns to the previous one.",
+                                ),
+                        )
+                        .arg(class_arg().conflicts_with("same-class"))
+                        .arg(
+                            Arg::new("same-class")
+                                .long("same-class")
+                                .short('s')
+                                .action(clap::ArgAction::SetTrue)
+                                .help("Only cycle through windows of the same class as the window"),
+                        )
+                        .arg(desktop_arg().help("Index or name of the desktop to cycle through or all")),
+                )
+                .subcommand(
+                    Command::new("close").visible_alias("c").about("Close a window").arg(
+                        Arg::new("force")
+                            .long("force")
+                            .short('f')
+                            .action(clap::ArgAction::SetTrue)
+                            .help("Kill the window's client rather than asking it to close"),
+                    ),
+                ),
         )
         .subcommand(
             Command::new("desktop")
@@ -54,69 +313,690 @@ fn cli() -> Command {
                 .subcommand_required(true)
                 .arg_required_else_help(true)
                 .subcommand(Command::new("list").visible_alias("l").about("List all desktops"))
-                .subcommand(Command::new("switch").visible_alias("s").about("Switch to a desktop"))
-                .subcommand(Command::new("close").visible_alias("c").about("Close a desktop")),
+                .subcommand(
+                    Command::new("switch")
+                        .visible_alias("s")
+                        .about("Switch to a desktop")
+                        .arg(Arg::new("DESKTOP").required(true).help("Index or name of the desktop")),
+                )
+                .subcommand(Command::new("close").visible_alias("c").about("Close the last desktop")),
         )
+        .subcommand(
+            Command::new("layout")
+                .about("Save and restore the layout of all windows e.g. after restarting the window manager")
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .subcommand(
+                    Command::new("save")
+                        .about("Save every window's desktop, geometry and state")
+                        .arg(Arg::new("NAME").required(true).help("Name to save the layout as")),
+                )
+                .subcommand(
+                    Command::new("restore")
+                        .about("Restore a saved layout matching windows by class, role, instance and title")
+                        .arg(Arg::new("NAME").required(true).help("Name the layout was saved as")),
+                ),
+        )
+        .subcommand(
+            Command::new("arrange")
+                .about("Arrange the normal windows of a desktop once in a layout")
+                .arg(
+                    Arg::new("LAYOUT")
+                        .required(true)
+                        .value_parser(|x: &str| Layout::try_from(x))
+                        .help("Layout e.g. tile, columns, rows, grid, monocle, cascade"),
+                )
+                .arg(desktop_arg().help("Index or name of the desktop to arrange"))
+                .arg(class_arg())
+                .arg(ratio_arg())
+                .arg(gap_arg()),
+        )
+        .subcommand(
+            Command::new("watch")
+                .about("Print window manager events as they happen one line per event until killed")
+                .arg(
+                    Arg::new("events")
+                        .long("events")
+                        .short('e')
+                        .value_name("EVENTS")
+                        .value_delimiter(',')
+                        .value_parser(WATCH_EVENTS)
+                        .help("Comma separated events to print, defaults to all events"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .short('f')
+                        .value_name("FORMAT")
+                        .default_value("json")
+                        .help("Print each event as a JSON line or using a template e.g. '{event} {class}: {name}'")
+                        .long_help(
+                            "Print each event as a JSON line or using a template e.g. '{event} {class}: {name}'. \
+                             Template fields are event, id, class, name, desktop, state, rect and area.",
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("tile-daemon")
+                .about("Keep the normal windows of each desktop tiled until killed")
+                .long_about(
+                    "Keep the normal windows of each desktop tiled until killed. Windows are retiled as they \
+                     open, close, are minimized or change state and when switching desktops. Docks, dialogs, \
+                     splash screens and windows that are fullscreen or kept above others are left floating.",
+                )
+                .arg(
+                    Arg::new("layout")
+                        .long("layout")
+                        .short('l')
+                        .value_name("LAYOUTS")
+                        .value_delimiter(',')
+                        .default_value("master-stack")
+                        .value_parser(|x: &str| Layout::try_from(x))
+                        .help("Comma separated layout for each desktop, the last is used for the rest")
+                        .long_help(
+                            "Comma separated layout for each desktop, the last is used for the rest. Layouts are \
+                             master-stack, columns, rows, grid, monocle and cascade.",
+                        ),
+                )
+                .arg(ratio_arg())
+                .arg(gap_arg()),
+        )
+        .subcommand(
+            Command::new("daemon")
+                .about("Apply the config file's rules to windows as they open until killed")
+                .long_about(
+                    "Apply the config file's rules to windows as they open until killed. Every matching rule \
+                     is applied once to each window. Windows are rechecked as their title, state or geometry \
+                     changes shortly after opening as applications often map windows before setting them.",
+                ),
+        )
+}
+
+// Window to activate relative to the given window
+#[derive(Clone, Copy)]
+enum Focus {
+    Dir(Direction), // nearest window in the direction
+    Cycle(bool),    // next window in most recently used order or when true the previous one
+}
+
+// Parse a direction or next or prev into the window to activate
+fn focus_dir(val: &str) -> Result<Focus, WindowManagerError> {
+    match val {
+        "next" => Ok(Focus::Cycle(false)),
+        "prev" => Ok(Focus::Cycle(true)),
+        _ => Direction::try_from(val).map(Focus::Dir),
+    }
+}
+
+// Parse a fraction between but excluding 0 and 1
+fn ratio(val: &str) -> Result<f32, String> {
+    let ratio = val.parse().ok().filter(|x| *x > 0.0 && *x < 1.0);
+    ratio.ok_or_else(|| "expected a number between 0 and 1".to_string())
+}
+
+// Parse a pair of numbers separated by the given character e.g. 3x2 or 0,0
+fn pair(val: &str, sep: char) -> Result<(u32, u32), String> {
+    val.split_once(sep)
+        .and_then(|(a, b)| Some((a.parse().ok()?, b.parse().ok()?)))
+        .ok_or_else(|| format!("expected <N>{}<N>", sep))
+}
+
+fn class_arg() -> Arg {
+    Arg::new("class")
+        .long("class")
+        .short('c')
+        .value_name("CLASS")
+        .value_parser(|x: &str| WinMatch::try_from(x).map(|x| WinSelector::from(vec![WinFilter::Class(x)])))
+        .help("Only include windows of the given class, wrap in slashes for a regex")
+}
+
+fn desktop_arg() -> Arg {
+    Arg::new("desktop").long("desktop").short('d').value_name("DESKTOP").default_value("current")
+}
+
+fn ratio_arg() -> Arg {
+    Arg::new("ratio")
+        .long("ratio")
+        .short('r')
+        .value_name("RATIO")
+        .default_value("0.55")
+        .value_parser(ratio)
+        .help("Fraction of the width given to the master window")
+}
+
+fn gap_arg() -> Arg {
+    Arg::new("gap")
+        .long("gap")
+        .short('g')
+        .value_name("PIXELS")
+        .default_value("0")
+        .value_parser(value_parser!(u32))
+        .help("Pixels to leave between windows and around the edges of the work area")
+}
+
+fn position_arg() -> Arg {
+    Arg::new("POSITION")
+        .required(true)
+        .value_parser(|x: &str| WinPosition::try_from(x))
+        .help("Pre-defined position e.g. center, left, top-right, bottom-center")
+}
+
+fn shape_arg() -> Arg {
+    Arg::new("SHAPE")
+        .required(true)
+        .value_parser(|x: &str| WinShape::try_from(x))
+        .help("Pre-defined shape e.g. max, unmax, halfw, halfh, small, medium, large, grow, shrink")
+}
+
+fn coord_arg(id: &'static str, other: &'static str) -> Arg {
+    Arg::new(id)
+        .requires(other)
+        .allow_negative_numbers(true)
+        .value_parser(value_parser!(i32))
+}
+
+fn state_arg(id: &'static str) -> Arg {
+    Arg::new(id).value_parser(|x: &str| WinState::try_from(x)).help(
+        "Window state e.g. above, below, sticky, shaded, fullscreen, skip-taskbar, skip-pager, \
+         demands-attention, max-horz, max-vert",
+    )
 }
 
 fn main() {
-    let _ = match cli().get_matches().subcommand() {
+    // Custom shapes and positions must be installed before the arguments are parsed
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        },
+    };
+    let args = expand_alias(std::env::args().collect(), &config);
+    config::init(config);
+
+    if let Err(err) = run(cli().get_matches_from(args)) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+// Expand a user defined alias given as the first argument into its whitespace separated arguments.
+// Aliases can't shadow the built-in commands.
+fn expand_alias(mut args: Vec<String>, config: &Config) -> Vec<String> {
+    let alias = args.get(1).filter(|x| cli().find_subcommand(x).is_none()).and_then(|x| config.aliases.get(x));
+    if let Some(alias) = alias {
+        let expanded: Vec<String> = alias.split_whitespace().map(String::from).collect();
+        args.splice(1..2, expanded);
+    }
+    args
+}
+
+fn run(matches: ArgMatches) -> Result<(), Box<dyn Error>> {
+    // Only the window commands take a selector
+    let selector = matches.subcommand_matches("window").and_then(|x| x.get_one::<WinSelector>("window"));
+    let output = matches.get_one::<String>("output").unwrap().as_str();
+    let monitor = matches.get_one::<String>("monitor");
+    let opt = |win: Option<u32>| match monitor {
+        Some(monitor) => WinOpt::new(win).monitor(monitor),
+        None => WinOpt::new(win),
+    };
+
+    match matches.subcommand() {
         Some(("window", sub)) => match sub.subcommand() {
-            Some(("list", _)) => libewmh::window::list(false),
-            Some(("move", _)) => WinOpt::new(None).pos(libewmh::WinPosition::Bottom).place(),
+            Some(("info", _)) => {
+                // Info is a single record by design so it is printed as an object
+                let (wm, win) = window::info(target(selector)?)?;
+                match output {
+                    "table" => print_info(&wm, &win),
+                    "json" => println!("{}", serde_json::to_string_pretty(&Info { manager: wm, window: win })?),
+                    _ => println!("{}", serde_json::to_string(&Info { manager: wm, window: win })?),
+                }
+            },
+            Some(("list", m)) => {
+                let wins = window::list(m.get_flag("all"), selector)?;
+                match output {
+                    "table" => print_win_table(&wins),
+                    _ => print_json(output, &wins)?,
+                }
+            },
+            Some(("move", m)) => {
+                let win = target(selector)?;
+                let pos = m.get_one::<WinPosition>("POSITION").unwrap().clone();
+                opt(win).pos(pos).place()?
+            },
+            Some(("shape", m)) => {
+                let win = target(selector)?;
+                let shape = m.get_one::<WinShape>("SHAPE").unwrap().clone();
+                opt(win).shape(shape).place()?
+            },
+            Some(("place", m)) => {
+                let win = target(selector)?;
+                if let Some((cols, rows)) = m.get_one::<(u32, u32)>("grid") {
+                    let (col, row) = m.get_one::<(u32, u32)>("cell").copied().unwrap_or((0, 0));
+                    let (col_span, row_span) = m.get_one::<(u32, u32)>("span").copied().unwrap_or((1, 1));
+                    opt(win).grid(Grid::new(*cols, *rows).cell(col, row).span(col_span, row_span)).place()?
+                } else {
+                    let shape = m.get_one::<WinShape>("SHAPE").unwrap().clone();
+                    let pos = m.get_one::<WinPosition>("POSITION").unwrap().clone();
+                    opt(win).shape(shape).pos(pos).place()?
+                }
+            },
+            Some(("static", m)) => {
+                let win = target(selector)?;
+                let w = *m.get_one::<u32>("W").unwrap();
+                let h = *m.get_one::<u32>("H").unwrap();
+                let mut opt = opt(win).size(w, h);
+                if let (Some(x), Some(y)) = (m.get_one::<i32>("X"), m.get_one::<i32>("Y")) {
+                    opt = opt.location(*x, *y);
+                }
+                opt.place()?
+            },
+            Some(("geometry", m)) => {
+                let win = target(selector)?;
+                let spec = *m.get_one::<GeometrySpec>("SPEC").unwrap();
+                opt(win).geometry(spec).place()?
+            },
+            Some(("state", m)) => {
+                let win = target(selector)?;
+                let action = m.get_one::<WinStateAction>("ACTION").unwrap().clone();
+                let state1 = m.get_one::<WinState>("STATE1").unwrap().clone();
+                let state2 = m.get_one::<WinState>("STATE2").cloned();
+                window::state(win, action, state1, state2)?
+            },
+            Some(("to-desktop", m)) => {
+                let win = target(selector)?;
+                window::to_desktop(win, m.get_one::<String>("DESKTOP").unwrap(), m.get_flag("follow"))?
+            },
+            Some(("to-monitor", m)) => {
+                window::to_monitor(target(selector)?, m.get_one::<String>("MONITOR").unwrap())?
+            },
+            Some(("focus", m)) => match m.get_one::<Focus>("DIRECTION") {
+                Some(Focus::Cycle(reverse)) => {
+                    let win = target(selector)?;
+                    let class = if m.get_flag("same-class") {
+                        Some(same_class(win)?)
+                    } else {
+                        m.get_one::<WinSelector>("class").cloned()
+                    };
+                    let desktop = m.get_one::<String>("desktop").map(|x| x.as_str()).filter(|x| *x != "current");
+                    window::focus_cycle(win, *reverse, desktop, class.as_ref())?
+                },
+                _ if m.contains_id("class")
+                    || m.get_flag("same-class")
+                    || m.value_source("desktop") == Some(ValueSource::CommandLine) =>
+                {
+                    return Err("--class, --same-class and --desktop only apply to next and prev".into());
+                },
+                Some(Focus::Dir(dir)) => window::focus_dir(target(selector)?, *dir)?,
+                None => window::focus(target(selector)?)?,
+            },
+            Some(("swap", m)) => {
+                let win = target(selector)?;
+                let other = m.get_one::<String>("TARGET").unwrap().as_str();
+                let other = match Direction::try_from(other) {
+                    Ok(dir) => window::neighbour(win, dir)?,
+                    Err(_) => Some(WinSelector::try_from(other)?.resolve_one(&WindowManager::connect()?)?),
+                };
+                if let Some(other) = other {
+                    window::swap(win, other)?
+                }
+            },
+            Some(("close", m)) => window::close(target(selector)?, m.get_flag("force"))?,
             _ => unreachable!(),
         },
+        Some(("desktop", sub)) => match sub.subcommand() {
+            Some(("list", _)) => {
+                let desktops = desktop::list()?;
+                match output {
+                    "table" => print_desktop_table(&desktops),
+                    _ => print_json(output, &desktops)?,
+                }
+            },
+            Some(("switch", m)) => desktop::switch(m.get_one::<String>("DESKTOP").unwrap())?,
+            Some(("close", _)) => desktop::close()?,
+            _ => unreachable!(),
+        },
+        Some(("layout", sub)) => match sub.subcommand() {
+            Some(("save", m)) => {
+                Snapshot::capture(&WindowManager::connect()?)?.save(m.get_one::<String>("NAME").unwrap())?
+            },
+            Some(("restore", m)) => {
+                let snapshot = Snapshot::load(m.get_one::<String>("NAME").unwrap())?;
+                let (restored, failed) = snapshot.restore(&WindowManager::connect()?)?;
+                if restored < snapshot.windows.len() {
+                    let total = snapshot.windows.len();
+                    eprintln!("warning: only {} of {} windows were restored", restored, total);
+                    for err in failed {
+                        eprintln!("warning: {}", err);
+                    }
+                }
+            },
+            _ => unreachable!(),
+        },
+        Some(("watch", m)) => {
+            let events = m.get_many::<String>("events").map(|x| x.cloned().collect::<Vec<_>>());
+            watch(&events.unwrap_or_default(), m.get_one::<String>("format").unwrap())?
+        },
+        Some(("arrange", m)) => {
+            let desktop = m.get_one::<String>("desktop").map(|x| x.as_str()).filter(|x| *x != "current");
+            desktop::tile(
+                desktop,
+                m.get_one::<WinSelector>("class"),
+                *m.get_one::<Layout>("LAYOUT").unwrap(),
+                *m.get_one::<f32>("ratio").unwrap(),
+                *m.get_one::<u32>("gap").unwrap(),
+            )?
+        },
+        Some(("daemon", _)) => daemon()?,
+        Some(("tile-daemon", m)) => {
+            let layouts = m.get_many::<Layout>("layout").unwrap().copied().collect::<Vec<_>>();
+            tile_daemon(&layouts, *m.get_one::<f32>("ratio").unwrap(), *m.get_one::<u32>("gap").unwrap())?
+        },
         _ => unreachable!(),
+    }
+    Ok(())
+}
+
+// Resolve the window selector into a single window id else None for the active window
+fn target(selector: Option<&WinSelector>) -> WindowManagerResult<Option<u32>> {
+    match selector {
+        Some(selector) => Ok(Some(selector.resolve_one(&WindowManager::connect()?)?)),
+        None => Ok(None),
+    }
+}
+
+// Build a selector matching the class of the given window else the active window
+fn same_class(win: Option<u32>) -> WindowManagerResult<WinSelector> {
+    let wm = WindowManager::connect()?;
+    let win = match win {
+        Some(win) => win,
+        None => wm.active_win()?,
     };
+    Ok(WinSelector::from(vec![WinFilter::Class(WinMatch::Exact(wm.win_class(win)?))]))
 }
 
-fn list_windows() -> WindowManagerResult<()> {
-    let wmcli = WindowManager::connect()?;
+// Event names that can be watched
+const WATCH_EVENTS: [&str; 9] =
+    ["focus", "open", "close", "desktop", "state", "send", "move", "title", "workarea"];
+
+// Window manager event along with the relevant window details for structured output
+#[derive(Default, Serialize)]
+struct Watch {
+    event: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    class: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    desktop: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state: Option<Vec<WinState>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rect: Option<Rect>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    area: Option<Rect>,
+}
 
-    for win in wmcli.get_windows(false)? {
-        print_win_details(&wmcli, win)?;
+// Print the given window manager events, or all if none are given, as they happen
+fn watch(events: &[String], format: &str) -> Result<(), Box<dyn Error>> {
+    let wm = WindowManager::connect()?;
+    let mut out = std::io::stdout().lock();
+    for event in wm.events()? {
+        let (mut watch, win) = match event? {
+            WmEvent::ActiveWindowChanged { win } => (Watch { event: "focus", ..Default::default() }, win),
+            WmEvent::WindowOpened { win } => (Watch { event: "open", ..Default::default() }, Some(win)),
+            WmEvent::WindowClosed { win } => (Watch { event: "close", id: Some(win), ..Default::default() }, None),
+            WmEvent::DesktopChanged { desktop } => {
+                (Watch { event: "desktop", desktop: Some(desktop as i32), ..Default::default() }, None)
+            },
+            WmEvent::WindowStateChanged { win, state } => {
+                (Watch { event: "state", state: Some(state), ..Default::default() }, Some(win))
+            },
+            WmEvent::WindowDesktopChanged { win, .. } => {
+                (Watch { event: "send", ..Default::default() }, Some(win))
+            },
+            WmEvent::WindowMoved { win, rect } => {
+                (Watch { event: "move", rect: Some(rect), ..Default::default() }, Some(win))
+            },
+            WmEvent::TitleChanged { win, .. } => (Watch { event: "title", ..Default::default() }, Some(win)),
+            WmEvent::WorkareaChanged { area } => {
+                (Watch { event: "workarea", area: Some(area), ..Default::default() }, None)
+            },
+        };
+        if !events.is_empty() && !events.iter().any(|x| x == watch.event) {
+            continue;
+        }
+
+        // Include the window's details when there is a window to describe
+        if let Some(win) = win {
+            watch.id = Some(win);
+            watch.class = wm.win_class(win).ok();
+            watch.name = wm.win_name(win).ok();
+            watch.desktop = wm.win_desktop(win).ok();
+        }
+
+        let line = match format {
+            "json" => serde_json::to_string(&watch)?,
+            _ => render(format, &watch)?,
+        };
+
+        // Stop quietly when the reader goes away e.g. a status bar restarting
+        if writeln!(out, "{}", line).and_then(|_| out.flush()).is_err() {
+            break;
+        }
     }
     Ok(())
 }
 
-// fn foo() {
-//     // Determine the target window
-//     let win = { matches.value_of("window").and_then(|x| x.parse::<u32>().ok()) };
-
-//     // info
-//     else if let Some(_) = matches.subcommand_matches("info") {
-//         libewmh::info(win).pass()?;
-
-//     // list
-//     } else if let Some(matches) = matches.subcommand_matches("list") {
-//         libewmh::list(matches.is_present("all")).pass()?;
-
-//     // move
-//     } else if let Some(ref matches) = matches.subcommand_matches("move") {
-//         let pos = WinPosition::try_from(matches.value_of("POSITION").unwrap()).pass()?;
-//         WinOpt::new(win).pos(pos).place().pass()?;
-
-//     // place
-//     } else if let Some(ref matches) = matches.subcommand_matches("place") {
-//         let shape = WinShape::try_from(matches.value_of("SHAPE").unwrap()).pass()?;
-//         let pos = WinPosition::try_from(matches.value_of("POSITION").unwrap()).pass()?;
-//         WinOpt::new(win).shape(shape).pos(pos).place().pass()?;
-
-//     // static
-//     } else if let Some(ref matches) = matches.subcommand_matches("static") {
-//         let w = matches.value_of("WIDTH").unwrap().parse::<u32>().pass()?;
-//         let h = matches.value_of("HEIGHT").unwrap().parse::<u32>().pass()?;
-//         let mut win = WinOpt::new(win).size(w, h);
-//         if matches.value_of("X").is_some() && matches.value_of("Y").is_some() {
-//             let x = matches.value_of("X").unwrap().parse::<u32>().pass()?;
-//             let y = matches.value_of("Y").unwrap().parse::<u32>().pass()?;
-//             win = win.location(x, y);
-//         }
-//         win.place().pass()?;
-
-//     // shape
-//     } else if let Some(ref matches) = matches.subcommand_matches("shape") {
-//         let shape = WinShape::try_from(matches.value_of("SHAPE").unwrap()).pass()?;
-//         WinOpt::new(win).shape(shape).place().pass()?;
-//     }
-// }
+// How long after a window opens that it is rechecked against the rules as its properties change
+const RULE_GRACE: Duration = Duration::from_secs(2);
+
+// Apply the config's rules to windows as they open. Each rule is applied at most once per window
+// and windows are rechecked for a short grace period to catch properties set after mapping.
+fn daemon() -> Result<(), Box<dyn Error>> {
+    let rules = &config::get().rules;
+    if rules.is_empty() {
+        return Err(format!("no rules were found in {}", config_path()).into());
+    }
+    let mut selectors = vec![];
+    for (i, rule) in rules.iter().enumerate() {
+        rule.check().map_err(|x| format!("rule {}: {}", i + 1, x))?;
+        selectors.push(rule.selector()?);
+    }
+
+    let wm = WindowManager::connect()?;
+    let mut pending: HashMap<u32, (Instant, Vec<usize>)> = HashMap::new();
+    for event in wm.events()? {
+        let win = match event? {
+            WmEvent::WindowOpened { win } => {
+                pending.insert(win, (Instant::now(), vec![]));
+                win
+            },
+            WmEvent::WindowClosed { win } => {
+                pending.remove(&win);
+                continue;
+            },
+            WmEvent::TitleChanged { win, .. }
+            | WmEvent::WindowMoved { win, .. }
+            | WmEvent::WindowStateChanged { win, .. } => win,
+            _ => continue,
+        };
+        pending.retain(|_, (opened, _)| opened.elapsed() < RULE_GRACE);
+        let applied = match pending.get_mut(&win) {
+            Some((_, applied)) => applied,
+            None => continue,
+        };
+        for (i, (rule, selector)) in rules.iter().zip(&selectors).enumerate() {
+            if applied.contains(&i) || !selector.is_match(&wm, win) {
+                continue;
+            }
+            applied.push(i);
+
+            // A failing rule shouldn't stop the daemon e.g. the window closed in the meantime
+            if let Err(err) = rule.apply(&wm, win) {
+                eprintln!("error: rule {}: window {}: {}", i + 1, win, err);
+            }
+        }
+    }
+    Ok(())
+}
+
+// Keep the current desktop tiled using its layout retiling whenever the set of tileable windows
+// might have changed. Moves aren't watched so that the daemon doesn't react to its own tiling.
+fn tile_daemon(layouts: &[Layout], ratio: f32, gap: u32) -> Result<(), Box<dyn Error>> {
+    let wm = WindowManager::connect()?;
+    let layout = |desktop: u32| *layouts.get(desktop as usize).or(layouts.last()).unwrap_or(&Layout::MasterStack);
+    let mut desktop = wm.current_desktop()?;
+
+    // Only the states deciding whether a window is tiled are tracked so that e.g. focus changes and
+    // the unmaximizing done while tiling don't trigger a retile
+    let floating = |state: &[WinState]| {
+        let has = |x: WinState| state.contains(&x);
+        (has(WinState::Above), has(WinState::Fullscreen), has(WinState::Hidden))
+    };
+    let mut states = HashMap::new();
+    for win in wm.get_windows(false)? {
+        states.insert(win.id, floating(&wm.win_state(win.id).unwrap_or_default()));
+    }
+
+    desktop::tile(None, None, layout(desktop), ratio, gap)?;
+    for event in wm.events()? {
+        match event? {
+            WmEvent::DesktopChanged { desktop: x } => desktop = x,
+            WmEvent::WindowOpened { win } => {
+                states.insert(win, floating(&wm.win_state(win).unwrap_or_default()));
+            },
+            WmEvent::WindowClosed { win } => {
+                states.remove(&win);
+            },
+            WmEvent::WindowStateChanged { win, state } => {
+                if states.insert(win, floating(&state)) == Some(floating(&state)) {
+                    continue;
+                }
+            },
+            WmEvent::WindowDesktopChanged { .. } | WmEvent::WorkareaChanged { .. } => (),
+            _ => continue,
+        }
+
+        // A failed retile shouldn't stop the daemon e.g. a window closed in the meantime
+        if let Err(err) = desktop::tile(None, None, layout(desktop), ratio, gap) {
+            eprintln!("error: {}", err);
+        }
+    }
+    Ok(())
+}
+
+// Describe the config file's location for messages
+fn config_path() -> String {
+    Config::path().map(|x| x.display().to_string()).unwrap_or_else(|| "the config file".to_string())
+}
+
+// Replace the `{field}` placeholders in the template with the record's values. Missing values
+// are replaced with an empty string and strings are inserted without quotes. The template is
+// scanned once so placeholders appearing within the inserted values e.g. a title are left alone.
+fn render<T: Serialize>(template: &str, record: &T) -> serde_json::Result<String> {
+    let fields = match serde_json::to_value(record)? {
+        serde_json::Value::Object(fields) => fields,
+        _ => Default::default(),
+    };
+    let known = ["event", "id", "class", "name", "desktop", "state", "rect", "area"];
+    let (mut line, mut rest) = (String::new(), template);
+    while let Some(start) = rest.find('{') {
+        line.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+        let key = rest.find('}').map(|end| &rest[..end]).filter(|x| known.contains(x));
+        match key {
+            Some(key) => {
+                match fields.get(key) {
+                    Some(serde_json::Value::String(x)) => line.push_str(x),
+                    Some(x) => line.push_str(&x.to_string()),
+                    None => (),
+                }
+                rest = &rest[key.len() + 1..];
+            },
+            None => line.push('{'),
+        }
+    }
+    line.push_str(rest);
+    Ok(line)
+}
+
+// Combined window manager and window information for structured output
+#[derive(Serialize)]
+struct Info {
+    manager: WmInfo,
+    window: WinInfo,
+}
+
+// Print the records as a pretty JSON array, even when there is only one, or as one compact JSON
+// object per line
+fn print_json<T: Serialize>(output: &str, records: &[T]) -> serde_json::Result<()> {
+    match output {
+        "json" => println!("{}", serde_json::to_string_pretty(records)?),
+        _ => {
+            for record in records {
+                println!("{}", serde_json::to_string(record)?);
+            }
+        },
+    }
+    Ok(())
+}
+
+fn print_info(wm: &WmInfo, win: &WinInfo) {
+    println!("X11 Information");
+    println!("-----------------------------------------------------------------------");
+    println!("Window Manager:    {}", wm.name);
+    println!("Composite Manager: {}", wm.composite_manager);
+    println!("Root Window:       {}", wm.root);
+    println!("Work area:         {}", wm.work_area);
+    println!("Screen Size:       {}x{}", wm.width, wm.height);
+    println!("Desktops:          {}", wm.desktops);
+    for (i, monitor) in wm.monitors.iter().enumerate() {
+        let primary = if monitor.primary { " (primary)" } else { "" };
+        println!("Monitor {}:         {} {}{}", i, monitor.name, monitor.rect, primary);
+    }
+    println!();
+    println!("Active Window");
+    println!("{:-<120}", "");
+    print_win_table(std::slice::from_ref(win));
+}
+
+fn print_win_table(wins: &[WinInfo]) {
+    println!(
+        "{:<8} {:<3} {:<6} {:<5} {:<5} {:<4} {:<4} {:<8} {:<7} {:<18} {:<18} NAME",
+        "ID", "DSK", "PID", "X", "Y", "W", "H", "BORDERS", "TYPE", "STATE", "CLASS"
+    );
+    for win in wins {
+        let (l, r, t, b) = win.borders;
+        println!(
+            "{:<8} {:<3} {:<6} {:<5} {:<5} {:<4} {:<4} {:<8} {:<7} {:<18} {:<18} {}",
+            format!("{:0>8}", win.id),
+            format!("{:>2}", win.desktop),
+            win.pid,
+            format!("{:<4}", win.x),
+            format!("{:<4}", win.y),
+            format!("{:<4}", win.w),
+            format!("{:<4}", win.h),
+            format!("{},{},{},{}", l, r, t, b),
+            win.typ.as_ref().map(|x| x.to_string()).unwrap_or_default(),
+            format!("{:?}", win.state),
+            win.class,
+            win.name
+        );
+    }
+}
+
+fn print_desktop_table(desktops: &[DesktopInfo]) {
+    println!("{:<3} {:<3} {:<4} NAME", "DSK", "CUR", "WINS");
+    for desktop in desktops {
+        println!(
+            "{:<3} {:<3} {:<4} {}",
+            format!("{:>2}", desktop.index),
+            if desktop.current { " *" } else { "" },
+            desktop.windows,
+            desktop.name
+        );
+    }
+}